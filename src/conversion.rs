@@ -0,0 +1,66 @@
+use std::{fmt::Display, path::Path};
+
+use crate::{
+    detection::detect_format_by_extension, AssSubtitle, Error, Format, SsaSubtitle, SubRipSubtitle,
+    Subtitle, TimedMicroDvdSubtitle, TimedSubtitleFile, WebVttSubtitle,
+};
+
+/// Convert the subtitle file at `input` into the format indicated by the extension of `output`,
+/// and write the result to `output`.
+///
+/// The format of the input file is detected automatically using its extension and contents.
+///
+/// # Errors
+///
+/// - Returns [`Error::FormatUnknownError`] if the output extension or the input format is not recognised
+/// - Returns [`Error::FileIoError`] if an error occurs while reading the input or writing the output
+pub fn convert_file(input: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<(), Error> {
+    let format = detect_format_by_extension(output.as_ref())?;
+    let subtitle = TimedSubtitleFile::new(input)?;
+
+    match format {
+        Format::Ass => AssSubtitle::from(subtitle).export(output),
+        Format::MicroDvd => TimedMicroDvdSubtitle::from(subtitle).export(output),
+        Format::Ssa => SsaSubtitle::from(subtitle).export(output),
+        Format::SubRip => SubRipSubtitle::from(subtitle).export(output),
+        Format::WebVtt => WebVttSubtitle::from(subtitle).export(output),
+    }
+}
+
+/// Information which would be dropped by converting a subtitle into another format
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConversionLoss {
+    /// Names of styles used by events, which cannot be represented in the target format
+    pub styles: Vec<String>,
+    /// Names of embedded fonts
+    pub fonts: Vec<String>,
+    /// Names of embedded graphics
+    pub graphics: Vec<String>,
+}
+
+impl ConversionLoss {
+    /// Whether no information would be lost
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.styles.is_empty() && self.fonts.is_empty() && self.graphics.is_empty()
+    }
+}
+
+impl Display for ConversionLoss {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "conversion would lose data:")?;
+        if !self.styles.is_empty() {
+            write!(f, " styles ({})", self.styles.join(", "))?;
+        }
+        if !self.fonts.is_empty() {
+            write!(f, " fonts ({})", self.fonts.join(", "))?;
+        }
+        if !self.graphics.is_empty() {
+            write!(f, " graphics ({})", self.graphics.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConversionLoss {}
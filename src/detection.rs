@@ -5,32 +5,41 @@ use std::{
 };
 
 use encoding_rs::Encoding;
-use encoding_rs_io::DecodeReaderBytesBuilder;
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
 
 use crate::{
     encoding::detect_file_encoding,
     microdvd::parse::parse_microdvd_line,
+    parsing::lines,
     subrip::parse::parse_new_line,
-    substation::common::parse::{parse_format, parse_script_info_heading},
+    substation::common::parse::{
+        parse_format, parse_format_from_section, parse_script_info_heading,
+    },
     webvtt::parse::parse_header,
     Error, Format,
 };
 
-/// Attempt to detect subtitle format from its extension first, then by file contents if that fails
+/// Attempt to detect subtitle format from its extension first, then by file contents if that fails.
+///
+/// Files with a `.sub` extension are always detected by their contents,
+/// since the extension is used by MicroDVD as well as other formats.
 ///
 /// # Errors
 ///
 /// - Returns [`Error::FileIoError`] if an error results while attempting to open the file for encoding detection or content detection.
 /// - Returns [`Error::FormatUnknownError`] if unable to conclusively determine a single format.
 pub fn detect_format(path: impl AsRef<Path>) -> Result<Format, Error> {
-    if let Ok(format) = detect_format_by_extension(path.as_ref()) {
-        return Ok(format);
-    }
+    let path = path.as_ref();
 
-    detect_format_by_content(path.as_ref())
+    detect_format_with_contents(path, || {
+        decode_file(path, detect_file_encoding(path, None).ok())
+    })
 }
 
-/// Attempt to detect subtitle format from its extension first, then by file contents using the given encoding if that fails
+/// Attempt to detect subtitle format from its extension first, then by file contents using the given encoding if that fails.
+///
+/// Files with a `.sub` extension are always detected by their contents,
+/// since the extension is used by MicroDVD as well as other formats.
 ///
 /// # Errors
 ///
@@ -40,14 +49,29 @@ pub fn detect_format_with_encoding(
     path: impl AsRef<Path>,
     encoding: Option<&'static Encoding>,
 ) -> Result<Format, Error> {
-    if let Ok(format) = detect_format_by_extension(path.as_ref()) {
-        return Ok(format);
-    }
+    let path = path.as_ref();
+
+    detect_format_with_contents(path, || decode_file(path, encoding))
+}
 
-    detect_format_by_content_with_encoding(path.as_ref(), encoding)
+/// Detect subtitle format from the extension of `path`, falling back to the decoded file contents
+/// returned by `contents` if the extension is not recognised.
+///
+/// `.sub` files are always detected by their contents, since the extension is shared between MicroDVD
+/// and other formats such as SubViewer. `contents` is only called if it is needed.
+pub(crate) fn detect_format_with_contents<R: BufRead>(
+    path: &Path,
+    contents: impl FnOnce() -> Result<R, Error>,
+) -> Result<Format, Error> {
+    match detect_format_by_extension(path) {
+        Ok(Format::MicroDvd) | Err(_) => detect_format_from_reader(contents()?),
+        Ok(format) => Ok(format),
+    }
 }
 
-/// Attempt to detect subtitle format using its file extension
+/// Attempt to detect subtitle format using its file extension.
+///
+/// Files with a `.sub` extension are assumed to be MicroDVD.
 ///
 /// # Errors
 ///
@@ -90,15 +114,27 @@ pub fn detect_format_by_content_with_encoding(
     path: impl AsRef<Path>,
     encoding: Option<&'static Encoding>,
 ) -> Result<Format, Error> {
+    detect_format_from_reader(decode_file(path.as_ref(), encoding)?)
+}
+
+/// Open file for reading, decoding it from the given encoding
+fn decode_file(
+    path: &Path,
+    encoding: Option<&'static Encoding>,
+) -> Result<BufReader<DecodeReaderBytes<File, Vec<u8>>>, Error> {
     let file = File::open(path)?;
     let transcoded = DecodeReaderBytesBuilder::new()
         .encoding(encoding)
         .build(file);
-    let reader = BufReader::new(transcoded);
 
+    Ok(BufReader::new(transcoded))
+}
+
+/// Detect subtitle format from the first few lines read from `reader`
+pub(crate) fn detect_format_from_reader(reader: impl BufRead) -> Result<Format, Error> {
     let mut texts = Vec::new();
     let mut counter = 0;
-    let lines = reader.lines();
+    let lines = lines(reader);
     for line in lines {
         let Ok(line) = line else {
             continue;
@@ -115,6 +151,8 @@ pub fn detect_format_by_content_with_encoding(
 
 /// Attempt to detect subtitle format from text of the first few lines of the subtitle
 ///
+/// Formats which are not supported, such as TTML, SAMI and LRC, are not detected as any supported format.
+///
 /// # Errors
 ///
 /// Returns [`Error::FormatUnknownError`] if unable to conclusively determine a single format.
@@ -132,6 +170,9 @@ pub fn detect_format_from_str(text: &str) -> Result<Format, Error> {
 
         return Ok(Format::Ass);
     }
+    if let Ok((_, format)) = parse_format_from_section(text) {
+        return Ok(format);
+    }
     if parse_microdvd_line(text).is_ok() {
         return Ok(Format::MicroDvd);
     }
@@ -0,0 +1,70 @@
+use crate::{Subtitle, TextEventInterface, TimedEventInterface};
+
+/// Single difference between the events of two subtitles
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SubtitleDiff {
+    /// Event at the given index of the new subtitle has no counterpart in the old subtitle
+    Added(usize),
+    /// Event at the given index of the old subtitle has no counterpart in the new subtitle
+    Removed(usize),
+    /// Matching events have different start or end times
+    TimingChanged {
+        /// Index of the event in the old subtitle
+        old: usize,
+        /// Index of the event in the new subtitle
+        new: usize,
+    },
+    /// Matching events have different text
+    TextChanged {
+        /// Index of the event in the old subtitle
+        old: usize,
+        /// Index of the event in the new subtitle
+        new: usize,
+    },
+}
+
+/// Compare the events of two subtitles, returning the differences needed to turn `old` into `new`.
+///
+/// Both subtitles are walked in order. Two events are considered to be the same event if their
+/// text is identical or their timings overlap, in which case any changes in timing or text are
+/// reported. Otherwise, whichever event starts first is reported as removed or added.
+/// An event whose timing and text have both changed produces both a
+/// [`SubtitleDiff::TimingChanged`] and a [`SubtitleDiff::TextChanged`] entry.
+pub fn diff<T>(old: &T, new: &T) -> Vec<SubtitleDiff>
+where
+    T: Subtitle,
+    T::Event: TimedEventInterface + TextEventInterface,
+{
+    let old_events = old.events();
+    let new_events = new.events();
+    let mut diffs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old_events.len() && j < new_events.len() {
+        let (a, b) = (&old_events[i], &new_events[j]);
+        let same_text = a.text() == b.text();
+        let overlapping = a.start() < b.end() && b.start() < a.end();
+
+        if same_text || overlapping {
+            if a.start() != b.start() || a.end() != b.end() {
+                diffs.push(SubtitleDiff::TimingChanged { old: i, new: j });
+            }
+            if !same_text {
+                diffs.push(SubtitleDiff::TextChanged { old: i, new: j });
+            }
+            i += 1;
+            j += 1;
+        } else if a.start() <= b.start() {
+            diffs.push(SubtitleDiff::Removed(i));
+            i += 1;
+        } else {
+            diffs.push(SubtitleDiff::Added(j));
+            j += 1;
+        }
+    }
+
+    diffs.extend((i..old_events.len()).map(SubtitleDiff::Removed));
+    diffs.extend((j..new_events.len()).map(SubtitleDiff::Added));
+
+    diffs
+}
@@ -1,20 +1,17 @@
 use std::{
+    borrow::Cow,
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, ErrorKind, Read},
     path::Path,
 };
 
 use chardetng::EncodingDetector;
-use encoding_rs::Encoding;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use encoding_rs_io::DecodeReaderBytesBuilder;
 
 use crate::errors::Error;
 
-pub(crate) fn detect_file_encoding(
-    path: &Path,
-    line_limit: Option<usize>,
-) -> Result<&'static Encoding, Error> {
-    let file = File::open(path)?;
-    let mut reader = BufReader::new(file);
+fn detect_encoding(mut reader: impl BufRead, line_limit: Option<usize>) -> &'static Encoding {
     let mut detector = EncodingDetector::new();
     let mut buffer: Vec<u8> = Vec::new();
 
@@ -34,5 +31,146 @@ pub(crate) fn detect_file_encoding(
         }
     }
 
-    Ok(detector.guess(None, true))
+    detector.guess(None, true)
+}
+
+pub(crate) fn detect_file_encoding(
+    path: &Path,
+    line_limit: Option<usize>,
+) -> Result<&'static Encoding, Error> {
+    let file = File::open(path)?;
+
+    Ok(detect_encoding(BufReader::new(file), line_limit))
+}
+
+/// Read the entire file at `path` and decode it, opening the file only once.
+///
+/// If no encoding is given, it is detected from the contents of the file, looking at only the first
+/// `line_limit` lines if the guess is confident enough by then. A byte order mark always takes precedence.
+pub(crate) fn read_file_with_encoding(
+    path: &Path,
+    encoding: Option<&'static Encoding>,
+    line_limit: Option<usize>,
+) -> Result<String, Error> {
+    decode_file(path, encoding, line_limit).map(|(text, _)| text)
+}
+
+/// Same as [`read_file_with_encoding`], but also returns the encoding which was actually used.
+pub(crate) fn decode_file(
+    path: &Path,
+    encoding: Option<&'static Encoding>,
+    line_limit: Option<usize>,
+) -> Result<(String, &'static Encoding), Error> {
+    let bytes = std::fs::read(path)?;
+    let encoding = encoding.unwrap_or_else(|| detect_encoding(bytes.as_slice(), line_limit));
+    let (text, used_encoding, _) = encoding.decode(bytes.as_slice());
+
+    Ok((text.into_owned(), used_encoding))
+}
+
+/// Reader which stops at the first error, keeping it so it can be reported once reading is done.
+struct StopOnError<R> {
+    inner: R,
+    error: Option<std::io::Error>,
+}
+
+impl<R: Read> Read for StopOnError<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.error.is_some() {
+            return Ok(0);
+        }
+
+        match self.inner.read(buf) {
+            Err(err) if err.kind() != ErrorKind::Interrupted => {
+                self.error = Some(err);
+                Ok(0)
+            }
+            result => result,
+        }
+    }
+}
+
+/// Parse text streamed from `reader` using the given parsing function.
+///
+/// Text is decoded as UTF-8 unless it starts with a byte order mark indicating otherwise.
+/// Parsing stops at the first error encountered while reading, which is then returned.
+pub(crate) fn parse_from_reader<T>(
+    reader: impl Read,
+    parse: impl FnOnce(&mut dyn Read) -> T,
+) -> Result<T, Error> {
+    let mut reader = StopOnError {
+        inner: reader,
+        error: None,
+    };
+    let parsed = {
+        let mut decoder = DecodeReaderBytesBuilder::new()
+            .encoding(Some(UTF_8))
+            .bom_override(true)
+            .strip_bom(true)
+            .build(&mut reader);
+
+        parse(&mut decoder)
+    };
+
+    match reader.error {
+        Some(err) => Err(err.into()),
+        None => Ok(parsed),
+    }
+}
+
+/// Encode text as UTF-16 with a byte order mark, which [`Encoding::encode`] does not support.
+///
+/// Returns `None` if the encoding is not UTF-16.
+fn encode_utf16(text: &str, encoding: &'static Encoding) -> Option<Vec<u8>> {
+    let to_bytes: fn(u16) -> [u8; 2] = if encoding == UTF_16LE {
+        u16::to_le_bytes
+    } else if encoding == UTF_16BE {
+        u16::to_be_bytes
+    } else {
+        return None;
+    };
+
+    Some(
+        std::iter::once(0xFEFF)
+            .chain(text.encode_utf16())
+            .flat_map(to_bytes)
+            .collect(),
+    )
+}
+
+/// Encode text, replacing characters which cannot be represented in the encoding with
+/// HTML numeric character references (e.g. `&#1605;`).
+///
+/// Also returns whether any characters were replaced. Fails if text cannot be encoded in the
+/// encoding at all (e.g. `replacement`).
+pub(crate) fn encode_text_lossy<'a>(
+    text: &'a str,
+    encoding: &'static Encoding,
+) -> Result<(Cow<'a, [u8]>, bool), Error> {
+    if let Some(bytes) = encode_utf16(text, encoding) {
+        return Ok((Cow::Owned(bytes), false));
+    }
+
+    let (bytes, output_encoding, had_errors) = encoding.encode(text);
+
+    if output_encoding == encoding {
+        Ok((bytes, had_errors))
+    } else {
+        Err(Error::UnsupportedEncodingError(encoding.name()))
+    }
+}
+
+/// Encode text, failing if any characters cannot be represented in the encoding,
+/// or if text cannot be encoded in the encoding at all (e.g. `replacement`).
+pub(crate) fn encode_text<'a>(
+    text: &'a str,
+    encoding: &'static Encoding,
+) -> Result<Cow<'a, [u8]>, Error> {
+    let (bytes, had_errors) = encode_text_lossy(text, encoding)?;
+
+    if had_errors {
+        Err(Error::EncodingError(encoding.name()))
+    } else {
+        Ok(bytes)
+    }
 }
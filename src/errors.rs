@@ -7,15 +7,36 @@ pub enum Error {
     FileIoError(std::io::Error),
     /// Error caused by being unable to detect subtitle format automatically
     FormatUnknownError,
+    /// Error caused by a string not matching any supported timestamp format
+    TimestampParseError(String),
+    /// Error caused by a line which could not be parsed when parsing strictly
+    ParseError {
+        /// Line number of the offending line, starting from 1
+        line: usize,
+        /// Description of the problem
+        message: String,
+    },
+    /// Error caused by text containing characters which cannot be represented in the given encoding
+    EncodingError(&'static str),
+    /// Error caused by trying to write text using an encoding which cannot be used for output,
+    /// such as `replacement`
+    UnsupportedEncodingError(&'static str),
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::FileIoError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl Debug for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self}")?;
-        if let Some(source) = std::error::Error::source(&self) {
-            writeln!(f, "Caused by:\n\t{source}")?;
+        if let Some(source) = std::error::Error::source(self) {
+            write!(f, "\nCaused by:\n\t{source}")?;
         }
         Ok(())
     }
@@ -31,6 +52,21 @@ impl Display for Error {
             Error::FormatUnknownError => {
                 write!(f, "could not detect subtitle format automatically")
             }
+            Error::TimestampParseError(input) => {
+                write!(f, "could not parse {input:?} as a timestamp")
+            }
+            Error::ParseError { line, message } => {
+                write!(f, "parse error on line {line}: {message}")
+            }
+            Error::EncodingError(encoding) => {
+                write!(
+                    f,
+                    "text contains characters which cannot be encoded as {encoding}"
+                )
+            }
+            Error::UnsupportedEncodingError(encoding) => {
+                write!(f, "{encoding} cannot be used as an output encoding")
+            }
         }
     }
 }
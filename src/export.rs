@@ -0,0 +1,43 @@
+use std::borrow::Cow;
+
+/// Options for writing subtitles to a file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Line ending to use for every line of the output
+    pub line_ending: LineEnding,
+    /// Whether to write a UTF-8 byte order mark (BOM) at the start of the output.
+    ///
+    /// Some players require one, while others fail to read files which have one.
+    /// A BOM is detected and removed when reading subtitles, so exported files can be read back either way.
+    pub write_bom: bool,
+}
+
+/// Line ending used when writing subtitles
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Unix style line endings (`\n`)
+    #[default]
+    Lf,
+    /// Windows style line endings (`\r\n`)
+    Crlf,
+}
+
+impl LineEnding {
+    /// Replace the `\n` line endings of text with this line ending
+    pub(crate) fn apply(self, text: &str) -> Cow<'_, str> {
+        match self {
+            LineEnding::Lf => Cow::Borrowed(text),
+            LineEnding::Crlf => Cow::Owned(text.replace('\n', "\r\n")),
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Encode text according to the options
+    pub(crate) fn encode(self, text: &str) -> Vec<u8> {
+        let text = self.line_ending.apply(text);
+        let bom = if self.write_bom { "\u{feff}" } else { "" };
+
+        [bom.as_bytes(), text.as_bytes()].concat()
+    }
+}
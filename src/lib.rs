@@ -20,18 +20,27 @@
     clippy::similar_names
 )]
 
+mod conversion;
 mod detection;
+mod diff;
 mod encoding;
 mod errors;
+mod export;
 /// MicroDVD (.sub) format subtitle implementations
 pub mod microdvd;
 mod parsing;
 /// Implementations for plain subtitles
 pub mod plain;
+mod punctuation;
+mod sdh;
+mod stats;
 /// SubRip (.srt) format subtitle implementations
 pub mod subrip;
 /// SubStation (.ass / .ssa) format subtitle implementations
 pub mod substation;
+/// Helpers for testing code which uses aspasia
+#[cfg(feature = "testing")]
+pub mod testing;
 mod timed_subtitle;
 /// Types used for subtitle timing
 pub mod timing;
@@ -39,15 +48,20 @@ mod traits;
 /// WebVTT (.vtt) format subtitle implementations
 pub mod webvtt;
 
+pub use conversion::{convert_file, ConversionLoss};
 pub use detection::{
     detect_format, detect_format_by_content, detect_format_by_content_with_encoding,
     detect_format_by_extension, detect_format_from_str, detect_format_with_encoding,
 };
+pub use diff::{diff, SubtitleDiff};
 pub use errors::Error;
+pub use export::{ExportOptions, LineEnding};
 #[doc(inline)]
 pub use microdvd::{MicroDvdSubtitle, TimedMicroDvdSubtitle};
 #[doc(inline)]
 pub use plain::PlainSubtitle;
+pub use punctuation::{EllipsisStyle, PunctuationOptions, QuoteStyle};
+pub use stats::{ReadingSpeedStats, SubtitleStats};
 #[doc(inline)]
 pub use subrip::SubRipSubtitle;
 #[doc(inline)]
@@ -55,8 +69,8 @@ pub use substation::{ass::AssSubtitle, ssa::SsaSubtitle};
 pub use timed_subtitle::{Format, TimedSubtitleFile};
 pub use timing::{Moment, TimeDelta};
 pub use traits::{
-    Subtitle, TextEvent, TextEventInterface, TextSubtitle, TimedEvent, TimedEventInterface,
-    TimedSubtitle,
+    IdentifiableEvent, RetainEvents, Subtitle, TextEvent, TextEventInterface, TextSubtitle,
+    TimedEvent, TimedEventInterface, TimedSubtitle, TryConvert,
 };
 #[doc(inline)]
 pub use webvtt::WebVttSubtitle;
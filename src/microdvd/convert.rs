@@ -0,0 +1,101 @@
+use nom::{
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::{char, one_of},
+    combinator::map,
+    sequence::{delimited, pair, preceded},
+    IResult, Parser,
+};
+
+/// Parse a colour control code (`{c:$bbggrr}` or `{C:$bbggrr}`), returning whether it applies to
+/// the whole event (uppercase) rather than a single line, and the colour in BGR hex
+fn colour_code(input: &str) -> IResult<&str, (bool, &str)> {
+    map(
+        delimited(
+            char('{'),
+            pair(
+                one_of("cC"),
+                preceded(
+                    tag(":$"),
+                    take_while_m_n(6, 6, |c: char| c.is_ascii_hexdigit()),
+                ),
+            ),
+            char('}'),
+        ),
+        |(kind, bgr)| (kind == 'C', bgr),
+    )
+    .parse(input)
+}
+
+/// Replace colour control codes using `open`, closing each colour with `close` at the end of
+/// its line (for `{c:...}`) or the end of the event (for `{C:...}`), and join lines with `line_break`.
+fn convert_colours(
+    text: &str,
+    open: impl Fn(&str) -> String,
+    close: &str,
+    line_break: &str,
+) -> String {
+    let mut event_colours = 0;
+    let mut lines = Vec::new();
+
+    for line in text.split('|') {
+        let mut result = String::new();
+        let mut line_colours = 0;
+        let mut remaining = line;
+
+        while let Some(c) = remaining.chars().next() {
+            if let Ok((rest, (whole_event, bgr))) = colour_code(remaining) {
+                result.push_str(open(bgr).as_str());
+                if whole_event {
+                    event_colours += 1;
+                } else {
+                    line_colours += 1;
+                }
+                remaining = rest;
+            } else {
+                result.push(c);
+                remaining = &remaining[c.len_utf8()..];
+            }
+        }
+
+        result.push_str(close.repeat(line_colours).as_str());
+        lines.push(result);
+    }
+
+    let mut result = lines.join(line_break);
+    result.push_str(close.repeat(event_colours).as_str());
+
+    result
+}
+
+/// Convert MicroDVD text to SubRip, turning colour codes into `<font color="#rrggbb">` tags
+pub(crate) fn mdvd_to_srt_formatting(text: &str) -> String {
+    convert_colours(
+        text,
+        |bgr| {
+            format!(
+                "<font color=\"#{}{}{}\">",
+                &bgr[4..6],
+                &bgr[2..4],
+                &bgr[0..2]
+            )
+            .to_ascii_lowercase()
+        },
+        "</font>",
+        "\n",
+    )
+}
+
+/// Convert MicroDVD text to SubStation, turning colour codes into `{\c&Hbbggrr&}` tags
+pub(crate) fn mdvd_to_substation_formatting(text: &str) -> String {
+    convert_colours(
+        text,
+        |bgr| format!("{{\\c&H{}&}}", bgr.to_ascii_uppercase()),
+        "{\\c}",
+        "\\N",
+    )
+}
+
+/// Convert MicroDVD text to WebVTT. WebVTT has no inline colour tags, so colour codes are removed.
+pub(crate) fn mdvd_to_vtt_formatting(text: &str) -> String {
+    convert_colours(text, |_| String::new(), "", "\n")
+}
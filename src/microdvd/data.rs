@@ -1,31 +1,41 @@
-use std::{borrow::Cow, fmt::Display, fs::File, io::BufReader, path::Path, str::FromStr};
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    io::{BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
 
 use encoding_rs::Encoding;
-use encoding_rs_io::DecodeReaderBytesBuilder;
 
 use crate::{
-    encoding::detect_file_encoding,
+    encoding::{parse_from_reader, read_file_with_encoding},
+    sdh::remove_sdh_annotations,
     timing::{frame_to_moment, moment_to_frame, Frame},
-    traits::TimedSubtitle,
-    AssSubtitle, Error, Moment, SsaSubtitle, SubRipSubtitle, Subtitle, TextEvent,
-    TextEventInterface, TextSubtitle, TimedEvent, TimedEventInterface, WebVttSubtitle,
+    traits::{mark_rtl_lines, RetainEvents, TimedSubtitle},
+    AssSubtitle, Error, IdentifiableEvent, Moment, SsaSubtitle, SubRipSubtitle, Subtitle,
+    TextEvent, TextEventInterface, TextSubtitle, TimedEvent, TimedEventInterface,
+    TimedSubtitleFile, WebVttSubtitle,
 };
 
-use super::parse::parse_microdvd;
+use super::parse::{parse_controls, parse_microdvd};
 
 type FrameRate = f32;
 
 /// Timed version of MicroDVD (.sub) subtitle, using user-supplied framerate to calculate timings
 ///
-/// When initialised without framerate, the default framerate is 24.
-#[derive(Debug)]
+/// When initialised without framerate, the framerate declared by the first event (e.g. `{1}{1}23.976`) is used,
+/// or the default framerate of 24 if there is none.
+#[derive(Clone, Debug)]
 pub struct TimedMicroDvdSubtitle {
     events: Vec<TimedMicroDvdEvent>,
     framerate: FrameRate,
+    /// Whether to write the framerate as the first event (e.g. `{1}{1}23.976`)
+    declares_framerate: bool,
 }
 
 /// Timed MicroDVD event
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TimedMicroDvdEvent {
     /// Start time of event
     pub start: Moment,
@@ -39,83 +49,136 @@ pub struct TimedMicroDvdEvent {
 ///
 /// This is not well supported, so things like conversion are not implemented for this type.
 /// If possible, use of [`MicroDvdSubtitle`], which represents subtitle events using actual timestamps, is better supported.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct MicroDvdSubtitle {
     events: Vec<MicroDvdEvent>,
 }
 
 /// Unmodified MicroDVD event, timed in terms of frames
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MicroDvdEvent {
     /// Frame at which event starts
     pub start: Frame,
     /// Frame at which event ends
     pub end: Frame,
-    /// Text to display during event
+    /// Control codes at the start of the event, which apply to the whole event
+    pub controls: Vec<MicroDvdControl>,
+    /// Text to display during event, not including leading control codes
     pub text: String,
 }
 
-impl TimedMicroDvdSubtitle {
-    fn open_file_with_encoding(
-        path: impl AsRef<Path>,
-        encoding: Option<&'static Encoding>,
-    ) -> Result<Self, Error> {
-        let file = File::open(path)?;
-        let transcoded = DecodeReaderBytesBuilder::new()
-            .encoding(encoding)
-            .build(file);
-        let reader = BufReader::new(transcoded);
+/// Control code applying to a whole MicroDVD event, given at the start of its text (e.g. `{Y:i}`).
+///
+/// Values are stored as written. Lowercase control codes, which apply only to a single line,
+/// are left in the text of the event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MicroDvdControl {
+    /// Font style (`{Y:...}`), e.g. `i` for italic or `b,u` for bold and underlined
+    Style(String),
+    /// Colour (`{C:...}`), as a BGR hex value prefixed with `$`, e.g. `$0000FF`
+    Colour(String),
+    /// Font name (`{F:...}`)
+    Font(String),
+    /// Font size (`{S:...}`)
+    Size(String),
+    /// Any other control code, such as position (`{P:...}`) or character set (`{H:...}`)
+    Other(char, String),
+}
 
-        Ok(Self::from_raw(&parse_microdvd(reader), Some(24.0)))
+impl MicroDvdControl {
+    /// Letter identifying the kind of control code
+    #[must_use]
+    pub fn code(&self) -> char {
+        match self {
+            MicroDvdControl::Style(_) => 'Y',
+            MicroDvdControl::Colour(_) => 'C',
+            MicroDvdControl::Font(_) => 'F',
+            MicroDvdControl::Size(_) => 'S',
+            MicroDvdControl::Other(code, _) => *code,
+        }
     }
 
-    fn open_file_with_encoding_and_framerate(
-        path: impl AsRef<Path>,
-        encoding: Option<&'static Encoding>,
-        framerate: FrameRate,
-    ) -> Result<Self, Error> {
-        let file = File::open(path)?;
-        let transcoded = DecodeReaderBytesBuilder::new()
-            .encoding(encoding)
-            .build(file);
-        let reader = BufReader::new(transcoded);
+    /// Value of the control code, as written
+    #[must_use]
+    pub fn value(&self) -> &str {
+        match self {
+            MicroDvdControl::Style(value)
+            | MicroDvdControl::Colour(value)
+            | MicroDvdControl::Font(value)
+            | MicroDvdControl::Size(value)
+            | MicroDvdControl::Other(_, value) => value.as_str(),
+        }
+    }
+}
 
-        Ok(Self::from_raw(&parse_microdvd(reader), Some(framerate)))
+impl Display for MicroDvdControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{{}:{}}}", self.code(), self.value())
     }
+}
 
+impl TimedMicroDvdSubtitle {
     /// Convert raw MicroDVD subtitle data to timed MicroDVD data, given the framerate the subtitles were created for.
-    /// If no framerate is given, the default of 24 is used.
+    ///
+    /// If no framerate is given, the framerate declared by the first event is used (see
+    /// [`MicroDvdSubtitle::declared_framerate`]), or the default of 24 if there is none.
+    /// The declaring event is not included in the timed events, but is written back when the subtitle is
+    /// written or converted to [`MicroDvdSubtitle`] (see [`TimedMicroDvdSubtitle::declares_framerate`]).
     #[must_use]
     pub fn from_raw(raw: &MicroDvdSubtitle, framerate: Option<FrameRate>) -> Self {
-        let framerate = framerate.unwrap_or(24.0);
+        let declared = raw.declared_framerate();
+        let framerate = framerate.or(declared).unwrap_or(24.0);
         let events = raw
             .events
             .iter()
+            .skip(usize::from(declared.is_some()))
             .map(|event| TimedMicroDvdEvent {
                 start: frame_to_moment(event.start, framerate),
                 end: frame_to_moment(event.end, framerate),
-                text: event.text.clone(),
+                text: event
+                    .controls
+                    .iter()
+                    .map(ToString::to_string)
+                    .chain(std::iter::once(event.text.clone()))
+                    .collect(),
             })
             .collect();
 
-        Self { events, framerate }
+        Self {
+            events,
+            framerate,
+            declares_framerate: declared.is_some(),
+        }
     }
 
     /// Create MicroDVD from path given and calculate its timings using the given framerate.
     ///
+    /// This takes precedence over any framerate declared by the first event, which is still removed.
+    ///
     /// # Errors
     ///
     /// Returns [`Error::FileIoError`] if an error occurs while opening the file
     pub fn with_framerate(path: impl AsRef<Path>, framerate: FrameRate) -> Result<Self, Error> {
-        let mut enc = detect_file_encoding(path.as_ref(), Some(30)).ok();
-        let mut result = Self::open_file_with_encoding_and_framerate(path.as_ref(), enc, framerate);
+        let text = read_file_with_encoding(path.as_ref(), None, Some(30))?;
+        let reader = BufReader::new(text.as_bytes());
 
-        if result.is_err() {
-            enc = detect_file_encoding(path.as_ref(), None).ok();
-            result = Self::open_file_with_encoding_and_framerate(path.as_ref(), enc, framerate);
-        }
+        Ok(Self::from_raw(&parse_microdvd(reader), Some(framerate)))
+    }
 
-        result
+    /// Create MicroDVD from the given reader and calculate its timings using the given framerate.
+    ///
+    /// See [`Subtitle::from_reader`] for details on how text is read.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::FileIoError`] if an error occurs while reading
+    pub fn from_reader_with_framerate(
+        reader: impl Read,
+        framerate: FrameRate,
+    ) -> Result<Self, Error> {
+        parse_from_reader(reader, |reader| {
+            Self::from_raw(&parse_microdvd(BufReader::new(reader)), Some(framerate))
+        })
     }
 
     /// Get framerate used to create timings
@@ -124,6 +187,19 @@ impl TimedMicroDvdSubtitle {
         self.framerate
     }
 
+    /// Whether the framerate is declared by writing it as the first event (e.g. `{1}{1}23.976`).
+    ///
+    /// This is the case if the subtitle was parsed from a file which declared its framerate.
+    #[must_use]
+    pub fn declares_framerate(&self) -> bool {
+        self.declares_framerate
+    }
+
+    /// Set whether to declare the framerate by writing it as the first event (e.g. `{1}{1}23.976`)
+    pub fn set_declares_framerate(&mut self, declares_framerate: bool) {
+        self.declares_framerate = declares_framerate;
+    }
+
     /// Modify framerate associated with subtitle.
     /// Does not modify event timings at all.
     pub fn set_framerate(&mut self, framerate: FrameRate) {
@@ -142,6 +218,28 @@ impl TimedMicroDvdSubtitle {
         }
         self.framerate = framerate;
     }
+
+    /// Get start and end frames of event at the given index, calculated using the subtitle's framerate
+    #[must_use]
+    pub fn event_frames(&self, index: usize) -> Option<(Frame, Frame)> {
+        self.events.get(index).map(|event| {
+            (
+                moment_to_frame(event.start, self.framerate),
+                moment_to_frame(event.end, self.framerate),
+            )
+        })
+    }
+
+    /// Modify timings of event at the given index using start and end frames.
+    /// Frames are converted to timings using the subtitle's framerate.
+    ///
+    /// Does nothing if there is no event at the given index.
+    pub fn set_event_frames(&mut self, index: usize, start: Frame, end: Frame) {
+        if let Some(event) = self.events.get_mut(index) {
+            event.start = frame_to_moment(start, self.framerate);
+            event.end = frame_to_moment(end, self.framerate);
+        }
+    }
 }
 
 impl Subtitle for TimedMicroDvdSubtitle {
@@ -151,15 +249,16 @@ impl Subtitle for TimedMicroDvdSubtitle {
         path: impl AsRef<std::path::Path>,
         encoding: Option<&'static encoding_rs::Encoding>,
     ) -> Result<Self, Error> {
-        let mut enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), Some(30)).ok());
-        let mut result = Self::open_file_with_encoding(path.as_ref(), enc);
+        let text = read_file_with_encoding(path.as_ref(), encoding, Some(30))?;
+        let reader = BufReader::new(text.as_bytes());
 
-        if encoding.is_none() && result.is_err() {
-            enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), None).ok());
-            result = Self::open_file_with_encoding(path.as_ref(), enc);
-        }
+        Ok(Self::from_raw(&parse_microdvd(reader), None))
+    }
 
-        result
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        parse_from_reader(reader, |reader| {
+            Self::from_raw(&parse_microdvd(BufReader::new(reader)), None)
+        })
     }
 
     fn events(&self) -> &[Self::Event] {
@@ -171,12 +270,21 @@ impl Subtitle for TimedMicroDvdSubtitle {
     }
 }
 
+impl RetainEvents for TimedMicroDvdSubtitle {
+    fn retain_events(&mut self, f: impl FnMut(&Self::Event) -> bool) {
+        self.events.retain(f);
+    }
+}
+
 impl TextSubtitle for TimedMicroDvdSubtitle {}
 
 impl TimedSubtitle for TimedMicroDvdSubtitle {}
 
 impl Display for TimedMicroDvdSubtitle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.declares_framerate {
+            writeln!(f, "{{1}}{{1}}{}", self.framerate)?;
+        }
         for event in &self.events {
             writeln!(
                 f,
@@ -214,6 +322,7 @@ impl From<&AssSubtitle> for TimedMicroDvdSubtitle {
                 })
                 .collect(),
             framerate: 24.0,
+            declares_framerate: false,
         }
     }
 }
@@ -231,6 +340,7 @@ impl From<&SsaSubtitle> for TimedMicroDvdSubtitle {
                 })
                 .collect(),
             framerate: 24.0,
+            declares_framerate: false,
         }
     }
 }
@@ -248,6 +358,7 @@ impl From<&SubRipSubtitle> for TimedMicroDvdSubtitle {
                 })
                 .collect(),
             framerate: 24.0,
+            declares_framerate: false,
         }
     }
 }
@@ -265,11 +376,62 @@ impl From<&WebVttSubtitle> for TimedMicroDvdSubtitle {
                 })
                 .collect(),
             framerate: 24.0,
+            declares_framerate: false,
+        }
+    }
+}
+
+impl From<AssSubtitle> for TimedMicroDvdSubtitle {
+    fn from(value: AssSubtitle) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<SsaSubtitle> for TimedMicroDvdSubtitle {
+    fn from(value: SsaSubtitle) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<SubRipSubtitle> for TimedMicroDvdSubtitle {
+    fn from(value: SubRipSubtitle) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<WebVttSubtitle> for TimedMicroDvdSubtitle {
+    fn from(value: WebVttSubtitle) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<TimedSubtitleFile> for TimedMicroDvdSubtitle {
+    fn from(value: TimedSubtitleFile) -> Self {
+        match value {
+            TimedSubtitleFile::MicroDvd(data) => data,
+            TimedSubtitleFile::Ass(data) => data.into(),
+            TimedSubtitleFile::Ssa(data) => data.into(),
+            TimedSubtitleFile::SubRip(data) => data.into(),
+            TimedSubtitleFile::WebVtt(data) => data.into(),
         }
     }
 }
 
+impl IdentifiableEvent for TimedMicroDvdEvent {
+    fn identifier(&self) -> Option<String> {
+        None
+    }
+}
+
 impl TextEvent for TimedMicroDvdEvent {
+    fn remove_sdh(&mut self) {
+        self.text = remove_sdh_annotations(self.text.as_str(), "|");
+    }
+
+    fn mark_rtl(&mut self) {
+        self.text = mark_rtl_lines(self.text.as_str(), "|");
+    }
+
     fn unformatted_text(&self) -> Cow<'_, String> {
         Cow::Borrowed(&self.text)
     }
@@ -310,16 +472,29 @@ impl TimedEventInterface for TimedMicroDvdEvent {
 }
 
 impl From<&TimedMicroDvdSubtitle> for MicroDvdSubtitle {
+    /// Convert to frame-based events using the framerate of the subtitle.
+    ///
+    /// If the subtitle declares its framerate, the declaration (e.g. `{1}{1}23.976`) is written as the first event.
     fn from(value: &TimedMicroDvdSubtitle) -> Self {
+        let declaration = value.declares_framerate.then(|| MicroDvdEvent {
+            start: Frame::from(1),
+            end: Frame::from(1),
+            controls: Vec::new(),
+            text: value.framerate.to_string(),
+        });
+
         Self {
-            events: value
-                .events
-                .iter()
-                .map(|event| MicroDvdEvent {
-                    start: moment_to_frame(event.start, value.framerate),
-                    end: moment_to_frame(event.end, value.framerate),
-                    text: event.text.clone(),
-                })
+            events: declaration
+                .into_iter()
+                .chain(value.events.iter().map(|event| {
+                    let (text, controls) = parse_controls(event.text.as_str());
+                    MicroDvdEvent {
+                        start: moment_to_frame(event.start, value.framerate),
+                        end: moment_to_frame(event.end, value.framerate),
+                        controls,
+                        text: text.to_string(),
+                    }
+                }))
                 .collect(),
         }
     }
@@ -332,17 +507,22 @@ impl MicroDvdSubtitle {
         Self { events }
     }
 
-    fn open_file_with_encoding(
-        path: impl AsRef<Path>,
-        encoding: Option<&'static Encoding>,
-    ) -> Result<Self, Error> {
-        let file = File::open(path)?;
-        let transcoded = DecodeReaderBytesBuilder::new()
-            .encoding(encoding)
-            .build(file);
-        let reader = BufReader::new(transcoded);
+    /// Framerate declared by the first event, if it has the form `{1}{1}23.976`.
+    ///
+    /// This is a common convention for indicating the framerate a subtitle was created for.
+    #[must_use]
+    pub fn declared_framerate(&self) -> Option<FrameRate> {
+        let first = self.events.first()?;
+        if i64::from(first.start) != 1 || i64::from(first.end) != 1 || !first.controls.is_empty() {
+            return None;
+        }
 
-        Ok(parse_microdvd(reader))
+        first
+            .text
+            .trim()
+            .parse::<FrameRate>()
+            .ok()
+            .filter(|framerate| framerate.is_finite() && *framerate > 0.0)
     }
 }
 
@@ -353,15 +533,14 @@ impl Subtitle for MicroDvdSubtitle {
         path: impl AsRef<Path>,
         encoding: Option<&'static Encoding>,
     ) -> Result<Self, Error> {
-        let mut enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), Some(30)).ok());
-        let mut result = Self::open_file_with_encoding(path.as_ref(), enc);
+        let text = read_file_with_encoding(path.as_ref(), encoding, Some(30))?;
+        let reader = BufReader::new(text.as_bytes());
 
-        if encoding.is_none() && result.is_err() {
-            enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), None).ok());
-            result = Self::open_file_with_encoding(path.as_ref(), enc);
-        }
+        Ok(parse_microdvd(reader))
+    }
 
-        result
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        parse_from_reader(reader, |reader| parse_microdvd(BufReader::new(reader)))
     }
 
     fn events(&self) -> &[Self::Event] {
@@ -373,12 +552,22 @@ impl Subtitle for MicroDvdSubtitle {
     }
 }
 
+impl RetainEvents for MicroDvdSubtitle {
+    fn retain_events(&mut self, f: impl FnMut(&Self::Event) -> bool) {
+        self.events.retain(f);
+    }
+}
+
 impl TextSubtitle for MicroDvdSubtitle {}
 
 impl Display for MicroDvdSubtitle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for event in &self.events {
-            writeln!(f, "{{{}}}{{{}}}{}", event.start, event.end, event.text)?;
+            write!(f, "{{{}}}{{{}}}", event.start, event.end)?;
+            for control in &event.controls {
+                write!(f, "{control}")?;
+            }
+            writeln!(f, "{}", event.text)?;
         }
 
         Ok(())
@@ -395,7 +584,21 @@ impl FromStr for MicroDvdSubtitle {
     }
 }
 
+impl IdentifiableEvent for MicroDvdEvent {
+    fn identifier(&self) -> Option<String> {
+        None
+    }
+}
+
 impl TextEvent for MicroDvdEvent {
+    fn remove_sdh(&mut self) {
+        self.text = remove_sdh_annotations(self.text.as_str(), "|");
+    }
+
+    fn mark_rtl(&mut self) {
+        self.text = mark_rtl_lines(self.text.as_str(), "|");
+    }
+
     fn unformatted_text(&self) -> Cow<'_, String> {
         Cow::Owned(self.text.replace('|', "\n"))
     }
@@ -1,4 +1,7 @@
+pub(crate) mod convert;
 mod data;
 pub(crate) mod parse;
 
-pub use data::{MicroDvdEvent, MicroDvdSubtitle, TimedMicroDvdEvent, TimedMicroDvdSubtitle};
+pub use data::{
+    MicroDvdControl, MicroDvdEvent, MicroDvdSubtitle, TimedMicroDvdEvent, TimedMicroDvdSubtitle,
+};
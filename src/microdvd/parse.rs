@@ -1,17 +1,18 @@
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufReader, Read};
 
 use nom::{
     branch::alt,
-    bytes::complete::take_until,
-    character::complete::{char, i64},
+    bytes::complete::{take_until, take_while},
+    character::complete::{char, i64, satisfy},
     combinator::{map, rest},
-    sequence::{delimited, pair},
+    multi::many0,
+    sequence::{delimited, pair, separated_pair},
     IResult, Parser,
 };
 
-use crate::{timing::Frame, MicroDvdSubtitle};
+use crate::{parsing::lines, timing::Frame, MicroDvdSubtitle};
 
-use super::MicroDvdEvent;
+use super::{MicroDvdControl, MicroDvdEvent};
 
 fn parse_frame(input: &str) -> IResult<&str, Frame> {
     map(delimited(char('{'), i64, char('}')), Frame::from).parse(input)
@@ -21,13 +22,49 @@ fn parse_frame_interval(input: &str) -> IResult<&str, (Frame, Frame)> {
     pair(parse_frame, parse_frame).parse(input)
 }
 
+fn parse_control(input: &str) -> IResult<&str, MicroDvdControl> {
+    map(
+        delimited(
+            char('{'),
+            separated_pair(
+                satisfy(|c| c.is_ascii_uppercase()),
+                char(':'),
+                take_while(|c| c != '}' && c != '{' && c != '|'),
+            ),
+            char('}'),
+        ),
+        |(code, value): (char, &str)| {
+            let value = value.to_string();
+            match code {
+                'Y' => MicroDvdControl::Style(value),
+                'C' => MicroDvdControl::Colour(value),
+                'F' => MicroDvdControl::Font(value),
+                'S' => MicroDvdControl::Size(value),
+                _ => MicroDvdControl::Other(code, value),
+            }
+        },
+    )
+    .parse(input)
+}
+
+/// Split control codes which apply to the whole event (e.g. `{Y:i}`) from the start of the text
+pub(crate) fn parse_controls(input: &str) -> (&str, Vec<MicroDvdControl>) {
+    many0(parse_control)
+        .parse(input)
+        .unwrap_or((input, Vec::new()))
+}
+
 pub(crate) fn parse_microdvd_line(input: &str) -> IResult<&str, MicroDvdEvent> {
     map(
         pair(parse_frame_interval, alt((take_until("\n"), rest))),
-        |((start, end), text)| MicroDvdEvent {
-            start,
-            end,
-            text: text.to_string(),
+        |((start, end), text)| {
+            let (text, controls) = parse_controls(text);
+            MicroDvdEvent {
+                start,
+                end,
+                controls,
+                text: text.to_string(),
+            }
         },
     )
     .parse(input)
@@ -35,7 +72,7 @@ pub(crate) fn parse_microdvd_line(input: &str) -> IResult<&str, MicroDvdEvent> {
 
 pub(crate) fn parse_microdvd<T: Read>(reader: BufReader<T>) -> MicroDvdSubtitle {
     let mut events = Vec::new();
-    for line in reader.lines() {
+    for line in lines(reader) {
         let Ok(line) = line else {
             continue;
         };
@@ -1,8 +1,10 @@
+use std::{collections::VecDeque, io::BufRead};
+
 use nom::{
     branch::alt,
     bytes::complete::take_until,
-    character::complete::{anychar, char, line_ending, multispace0},
-    combinator::{eof, map, value},
+    character::complete::{anychar, char, digit1, line_ending, multispace0},
+    combinator::{eof, map, map_res, value},
     error::ParseError,
     multi::many_till,
     sequence::{delimited, pair},
@@ -36,3 +38,55 @@ pub(crate) fn bracket_tag(input: &str) -> IResult<&str, &str> {
 pub(crate) fn html_tag(input: &str) -> IResult<&str, &str> {
     delimited(char('<'), take_until(">"), char('>')).parse(input)
 }
+
+/// Parse the fractional part of a timestamp into milliseconds, according to its number of digits
+/// (e.g. `5` -> 500ms, `50` -> 500ms, `500` -> 500ms). Digits past millisecond precision are ignored.
+pub(crate) fn fraction_ms(input: &str) -> IResult<&str, i64> {
+    map_res(digit1, |digits: &str| {
+        digits
+            .chars()
+            .chain(std::iter::repeat('0'))
+            .take(3)
+            .collect::<String>()
+            .parse::<i64>()
+    })
+    .parse(input)
+}
+
+/// Iterator over the lines of a reader, treating `\n`, `\r\n` and lone `\r` as line endings
+pub(crate) struct Lines<B> {
+    reader: B,
+    pending: VecDeque<String>,
+}
+
+impl<B: BufRead> Iterator for Lines<B> {
+    type Item = std::io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(line) = self.pending.pop_front() {
+            return Some(Ok(line));
+        }
+
+        let mut buffer = String::new();
+        match self.reader.read_line(&mut buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                let content = buffer.strip_suffix('\n').unwrap_or(buffer.as_str());
+                let content = content.strip_suffix('\r').unwrap_or(content);
+                self.pending
+                    .extend(content.split('\r').map(std::string::ToString::to_string));
+
+                self.pending.pop_front().map(Ok)
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// Get lines of a reader, normalizing mixed line endings
+pub(crate) fn lines<B: BufRead>(reader: B) -> Lines<B> {
+    Lines {
+        reader,
+        pending: VecDeque::new(),
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    traits::TimedSubtitle, Moment, TextEvent, TextSubtitle, TimedEvent, TimedEventInterface,
-    TimedSubtitleFile,
+    traits::TimedSubtitle, IdentifiableEvent, Moment, TextEvent, TextSubtitle, TimeDelta,
+    TimedEvent, TimedEventInterface, TimedSubtitleFile,
 };
 
 /// Basic subtitle data containing only the textual content and start/end timing with no style or formatting information
@@ -10,7 +10,7 @@ pub struct PlainSubtitle {
 }
 
 /// A basic event, containing only the most essential information: text to display without any formatting, and the start and end time.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PlainEvent {
     /// Textual content for the event. Should not contain any formatting tags.
     pub text: String,
@@ -20,6 +20,12 @@ pub struct PlainEvent {
     pub end: Moment,
 }
 
+impl IdentifiableEvent for PlainEvent {
+    fn identifier(&self) -> Option<String> {
+        None
+    }
+}
+
 impl Default for PlainSubtitle {
     fn default() -> Self {
         Self::new()
@@ -39,6 +45,28 @@ impl PlainSubtitle {
         Self { events }
     }
 
+    /// Create new instance from lines of text, giving each line its own event lasting `per_line`.
+    /// Events are placed back to back, with the first starting at `start`.
+    #[must_use]
+    pub fn from_lines(lines: &[&str], start: Moment, per_line: TimeDelta) -> Self {
+        let mut current = start;
+
+        Self {
+            events: lines
+                .iter()
+                .map(|line| {
+                    let event = PlainEvent {
+                        text: (*line).to_string(),
+                        start: current,
+                        end: current + per_line,
+                    };
+                    current = event.end;
+                    event
+                })
+                .collect(),
+        }
+    }
+
     /// Get list of events as a slice
     #[must_use]
     pub fn events(&self) -> &[PlainEvent] {
@@ -0,0 +1,97 @@
+use nom::{branch::alt, combinator::recognize, Parser};
+
+use crate::parsing::{bracket_tag, html_tag};
+
+/// Options for normalizing punctuation in subtitle text.
+///
+/// Punctuation for which no style is given is left unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PunctuationOptions {
+    /// Style to convert quotation marks and apostrophes to
+    pub quotes: Option<QuoteStyle>,
+    /// Style to convert ellipses to
+    pub ellipsis: Option<EllipsisStyle>,
+}
+
+/// Style of quotation marks and apostrophes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// Straight quotes (`"` and `'`)
+    Straight,
+    /// Curly quotes (`“`, `”`, `‘` and `’`)
+    Curly,
+}
+
+/// Style of ellipses
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EllipsisStyle {
+    /// Three full stops (`...`)
+    ThreeDots,
+    /// Single ellipsis character (`…`)
+    Single,
+}
+
+/// Normalize quotes and ellipses in text according to the given options.
+///
+/// Formatting tags (`<i>`, `{\i1}`) are left untouched, and SubStation line breaks (`\N`, `\n`, `\h`)
+/// are treated as whitespace when deciding whether a curly quote opens or closes.
+pub(crate) fn normalize_punctuation(text: &str, options: PunctuationOptions) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut previous: Option<char> = None;
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if let Ok((remaining, tag)) = recognize(alt((html_tag, bracket_tag))).parse(rest) {
+            output.push_str(tag);
+            rest = remaining;
+            continue;
+        }
+        if let Some(remaining) = ["\\N", "\\n", "\\h"]
+            .iter()
+            .find_map(|line_break| rest.strip_prefix(line_break))
+        {
+            output.push_str(&rest[..rest.len() - remaining.len()]);
+            previous = Some(' ');
+            rest = remaining;
+            continue;
+        }
+        if options.ellipsis == Some(EllipsisStyle::Single) && rest.starts_with("...") {
+            output.push('\u{2026}');
+            previous = Some('\u{2026}');
+            rest = &rest[3..];
+            continue;
+        }
+
+        rest = &rest[c.len_utf8()..];
+        let next = rest.chars().next();
+        match (c, options.quotes, options.ellipsis) {
+            ('\u{2026}', _, Some(EllipsisStyle::ThreeDots)) => output.push_str("..."),
+            ('\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}', Some(QuoteStyle::Straight), _) => {
+                output.push('"');
+            }
+            ('\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}', Some(QuoteStyle::Straight), _) => {
+                output.push('\'');
+            }
+            ('"', Some(QuoteStyle::Curly), _) => {
+                output.push(if opens_quote(previous) {
+                    '\u{201C}'
+                } else {
+                    '\u{201D}'
+                });
+            }
+            ('\'', Some(QuoteStyle::Curly), _) => {
+                let opening = opens_quote(previous) && next.is_some_and(|n| !n.is_whitespace());
+                output.push(if opening { '\u{2018}' } else { '\u{2019}' });
+            }
+            _ => output.push(c),
+        }
+        previous = Some(c);
+    }
+
+    output
+}
+
+/// Whether a quote following the given character starts a quotation, rather than ending one
+fn opens_quote(previous: Option<char>) -> bool {
+    previous.is_none_or(|c| c.is_whitespace() || "([{-\u{2013}\u{2014}\u{201C}\u{2018}".contains(c))
+}
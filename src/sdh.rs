@@ -0,0 +1,125 @@
+use crate::subrip::parse::strip_srt_formatting;
+
+/// Remove hearing impaired annotations from subtitle text, where lines are separated by `line_break`.
+///
+/// This removes sound descriptions in square brackets, parenthesised descriptions which make up a
+/// whole line or are written in capitals, and speaker labels in capitals at the start of a line.
+/// Lines which are left empty are removed, with any override blocks at their start (e.g. `{\pos(10,20)}`)
+/// moved to the start of the next remaining line. Whitespace is only normalised on lines which were changed.
+pub(crate) fn remove_sdh_annotations(text: &str, line_break: &str) -> String {
+    let mut lines = Vec::new();
+    let mut pending_blocks = String::new();
+
+    for line in text.split(line_break) {
+        match remove_sdh_from_line(line) {
+            Some(line) => lines.push(format!("{}{line}", std::mem::take(&mut pending_blocks))),
+            None => pending_blocks.push_str(leading_blocks(line)),
+        }
+    }
+
+    lines.join(line_break)
+}
+
+fn remove_sdh_from_line(line: &str) -> Option<String> {
+    let stripped = remove_enclosed(line, '[', ']', |_| true);
+    let without_parentheses = remove_enclosed(stripped.as_str(), '(', ')', |_| true);
+    let stripped = if is_blank(without_parentheses.as_str()) {
+        without_parentheses
+    } else {
+        remove_enclosed(stripped.as_str(), '(', ')', is_capitalised)
+    };
+    let stripped = remove_speaker_label(stripped.as_str());
+
+    if is_blank(stripped.as_str()) {
+        None
+    } else if stripped == line {
+        Some(stripped)
+    } else {
+        Some(stripped.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+}
+
+/// Override blocks (`{...}`) at the start of a line
+fn leading_blocks(line: &str) -> &str {
+    let mut end = 0;
+
+    while let Some(block) = line[end..].strip_prefix('{') {
+        let Some(length) = block.find('}') else {
+            break;
+        };
+        end += length + 2;
+    }
+
+    &line[..end]
+}
+
+/// Whether a line has no content other than formatting tags, whitespace or dialogue dashes
+fn is_blank(line: &str) -> bool {
+    let visible = strip_srt_formatting(line).map_or_else(|_| line.to_string(), |(_, s)| s);
+
+    visible
+        .chars()
+        .all(|c| c.is_whitespace() || c == '-' || c == '\u{2010}' || c == '\u{2013}')
+}
+
+fn is_capitalised(content: &str) -> bool {
+    content.chars().any(char::is_alphabetic) && !content.chars().any(char::is_lowercase)
+}
+
+/// Remove all segments enclosed by `open` and `close` that satisfy `predicate`.
+/// Characters within formatting tags (`<...>` and `{...}`) are ignored.
+fn remove_enclosed(
+    line: &str,
+    open: char,
+    close: char,
+    predicate: impl Fn(&str) -> bool,
+) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut remaining = line;
+    let mut tag_end = None;
+
+    while let Some(c) = remaining.chars().next() {
+        let width = c.len_utf8();
+        match tag_end {
+            Some(end) if c == end => tag_end = None,
+            None if c == '<' => tag_end = Some('>'),
+            None if c == '{' => tag_end = Some('}'),
+            None if c == open => {
+                if let Some(length) = remaining[width..].find(close) {
+                    if predicate(&remaining[width..width + length]) {
+                        remaining = &remaining[width + length + close.len_utf8()..];
+                        continue;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        result.push(c);
+        remaining = &remaining[width..];
+    }
+
+    result
+}
+
+/// Remove a speaker label in capitals (e.g. `JOHN:`) from the start of a line,
+/// keeping any leading dialogue dash
+fn remove_speaker_label(line: &str) -> String {
+    let content = line.trim_start_matches(|c: char| c.is_whitespace() || c == '-');
+    let prefix = &line[..line.len() - content.len()];
+
+    let Some((label, rest)) = content.split_once(':') else {
+        return line.to_string();
+    };
+
+    let is_label = is_capitalised(label)
+        && label.chars().all(|c| {
+            c.is_alphanumeric() || c.is_whitespace() || matches!(c, '.' | '\'' | '-' | '&' | '#')
+        });
+
+    if is_label {
+        format!("{prefix}{}", rest.trim_start())
+    } else {
+        line.to_string()
+    }
+}
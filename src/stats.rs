@@ -0,0 +1,31 @@
+use crate::TimeDelta;
+
+/// Reading speed statistics for a subtitle, measured in characters per second
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReadingSpeedStats {
+    /// Mean reading speed across all events
+    pub avg_cps: f64,
+    /// Highest reading speed of any single event
+    pub max_cps: f64,
+    /// Number of events with a reading speed above the given threshold
+    pub events_over: usize,
+}
+
+/// Summary statistics for a timed subtitle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SubtitleStats {
+    /// Number of events
+    pub event_count: usize,
+    /// Time between the start of the earliest event and the end of the latest event
+    pub total_duration: TimeDelta,
+    /// Sum of the durations of all events
+    pub display_time: TimeDelta,
+    /// Mean reading speed across all events, in characters per second
+    pub avg_cps: f64,
+    /// Highest reading speed of any single event, in characters per second
+    pub max_cps: f64,
+    /// Number of characters in the longest line of text
+    pub longest_line: usize,
+    /// Number of events which start before a preceding event has ended
+    pub overlaps: usize,
+}
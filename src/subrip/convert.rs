@@ -9,6 +9,8 @@ use nom::{
 
 use crate::parsing::{bracket_tag, discard, html_tag};
 
+use super::SubRipCoordinates;
+
 fn convert_to_ass_tag(input: &str) -> IResult<&str, &str> {
     alt((
         convert_to_ssa_tag,
@@ -134,3 +136,26 @@ pub(crate) fn srt_to_vtt_formatting(input: &str) -> IResult<&str, String> {
     )
     .parse(input)
 }
+
+/// Width of the canvas assumed for SubRip coordinates
+const ASSUMED_WIDTH: f64 = 1280.0;
+/// Height of the canvas assumed for SubRip coordinates
+const ASSUMED_HEIGHT: f64 = 720.0;
+
+/// Convert SubRip coordinates (`X1:100 X2:600 Y1:500 Y2:550`) to WebVTT `position` and `line`
+/// cue settings, as percentages of an assumed 1280x720 canvas.
+///
+/// The position is taken from the horizontal centre of the box, and the line from its top edge.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn srt_coordinates_to_vtt_settings(coordinates: &SubRipCoordinates) -> String {
+    let percentage = |value: f64, size: f64| (value / size * 100.0).clamp(0.0, 100.0).round();
+
+    format!(
+        "position:{}% line:{}%",
+        percentage(
+            f64::midpoint(coordinates.x1 as f64, coordinates.x2 as f64),
+            ASSUMED_WIDTH
+        ),
+        percentage(coordinates.y1 as f64, ASSUMED_HEIGHT)
+    )
+}
@@ -1,23 +1,34 @@
-use std::{borrow::Cow, fmt::Display, fs::File, io::BufReader, path::Path, str::FromStr};
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    io::{BufReader, Read},
+    ops::Range,
+    path::Path,
+    str::FromStr,
+};
 
 use encoding_rs::Encoding;
-use encoding_rs_io::DecodeReaderBytesBuilder;
 
 use crate::{
-    encoding::detect_file_encoding,
+    encoding::{parse_from_reader, read_file_with_encoding},
     errors::Error,
+    microdvd::convert::mdvd_to_srt_formatting,
     plain::PlainSubtitle,
     substation::{
-        ass::convert::ass_to_srt_formatting, common::convert::split_formatting_tags,
+        ass::{convert::ass_to_srt_formatting, AssStyle},
+        common::convert::{reopen_tags_across_lines, split_formatting_tags},
         ssa::convert::ssa_to_srt_formatting,
     },
-    traits::TimedSubtitle,
+    traits::{split_events_at, RetainEvents, TimedSubtitle},
     webvtt::convert::vtt_to_srt_formatting,
-    AssSubtitle, Moment, SsaSubtitle, Subtitle, TextEvent, TextEventInterface, TextSubtitle,
-    TimedEvent, TimedEventInterface, TimedMicroDvdSubtitle, TimedSubtitleFile, WebVttSubtitle,
+    AssSubtitle, ConversionLoss, IdentifiableEvent, Moment, SsaSubtitle, Subtitle, TextEvent,
+    TextEventInterface, TextSubtitle, TimeDelta, TimedEvent, TimedEventInterface,
+    TimedMicroDvdSubtitle, TimedSubtitleFile, TryConvert, WebVttSubtitle,
 };
 
-use super::parse::{parse_srt, strip_srt_formatting};
+use super::parse::{
+    find_invalid_line, parse_coordinates, parse_srt, strip_srt_formatting, strip_srt_tag,
+};
 
 /// SubRip (.srt) subtitle data, containing only a list of events.
 #[derive(Clone, Debug)]
@@ -26,7 +37,7 @@ pub struct SubRipSubtitle {
 }
 
 /// SubRip subtitle event
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SubRipEvent {
     /// Line number for the event. Generally this should be a sequential range of numbers, from 1 to however many lines there are.
     pub line_number: usize,
@@ -40,6 +51,19 @@ pub struct SubRipEvent {
     pub coordinates: Option<String>,
 }
 
+/// Extended SubRip coordinates of the box in which an event's text is displayed, such as `X1:100 X2:600 Y1:500 Y2:550`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SubRipCoordinates {
+    /// Left edge of the box
+    pub x1: i64,
+    /// Right edge of the box
+    pub x2: i64,
+    /// Top edge of the box
+    pub y1: i64,
+    /// Bottom edge of the box
+    pub y2: i64,
+}
+
 impl SubRipSubtitle {
     /// Creates a new SubRip (.srt) subtitle from an already existing list of `SubRipEvent`s
     #[must_use]
@@ -57,23 +81,110 @@ impl SubRipSubtitle {
         }
     }
 
-    fn try_from_path_with_encoding(
-        path: &Path,
-        encoding: Option<&'static Encoding>,
-    ) -> Result<Self, Error> {
-        let file = File::open(path)?;
-        let transcoded = DecodeReaderBytesBuilder::new()
-            .encoding(encoding)
-            .build(file);
-        let reader = BufReader::new(transcoded);
+    /// Whether events are numbered sequentially from 1 in the order they are stored in,
+    /// i.e. whether [`SubRipSubtitle::renumber`] would leave them unchanged.
+    #[must_use]
+    pub fn has_sequential_numbering(&self) -> bool {
+        self.numbering_gaps().is_empty()
+    }
 
-        Ok(parse_srt(reader))
+    /// Get the indices of events whose line number does not match their position,
+    /// when numbering sequentially from 1.
+    #[must_use]
+    pub fn numbering_gaps(&self) -> Vec<usize> {
+        self.events
+            .iter()
+            .enumerate()
+            .filter(|(i, event)| event.line_number != i + 1)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Write subtitles to file at the given path, with events numbered sequentially from `start`
+    /// according to the order they are stored in.
+    ///
+    /// Unlike [`SubRipSubtitle::renumber`], this does not modify the line numbers of the events.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::FileIoError`] if method fails to create file at the specified path
+    pub fn export_renumbered(&self, path: impl AsRef<Path>, start: usize) -> Result<(), Error> {
+        let mut renumbered = self.clone();
+        for (i, event) in renumbered.events.iter_mut().enumerate() {
+            event.line_number = start + i;
+        }
+
+        renumbered.export(path)
+    }
+
+    /// Shift only the events at or after `start_index` by the given amount of time.
+    ///
+    /// Useful for making room for an event inserted partway through the subtitle.
+    /// Does nothing if `start_index` is out of bounds.
+    pub fn shift_from(&mut self, start_index: usize, delta: TimeDelta) {
+        self.shift_range(start_index..self.events.len(), delta);
+    }
+
+    /// Shift only the events with indices in the given range by the given amount of time.
+    ///
+    /// Indices beyond the last event are ignored.
+    pub fn shift_range(&mut self, range: Range<usize>, delta: TimeDelta) {
+        let end = range.end.min(self.events.len());
+        let start = range.start.min(end);
+
+        for event in &mut self.events[start..end] {
+            event.shift(delta);
+        }
+    }
+
+    /// Append the events of `other` after the events of this subtitle, shifted by `offset`,
+    /// then renumber all events starting from 1.
+    ///
+    /// Useful for joining subtitles for a video which was split into several parts,
+    /// where `offset` would be the duration of the preceding parts.
+    pub fn merge(&mut self, other: &SubRipSubtitle, offset: TimeDelta) {
+        self.events.extend(other.events.iter().map(|event| {
+            let mut event = event.clone();
+            event.shift(offset);
+            event
+        }));
+        self.renumber();
+    }
+
+    /// Parse subtitles strictly, failing instead of skipping over content which is not valid SubRip.
+    ///
+    /// Each block must begin with an event number, followed by a timing line. This also rejects
+    /// text containing blank lines, which is accepted when parsing normally.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] identifying the first line which could not be parsed.
+    pub fn from_str_strict(s: &str) -> Result<Self, Error> {
+        let s = s.strip_prefix('\u{feff}').unwrap_or(s);
+        if let Some((line, message)) = find_invalid_line(s) {
+            return Err(Error::ParseError { line, message });
+        }
+
+        Self::from_str(s)
     }
 }
 
 impl TextSubtitle for SubRipSubtitle {}
 
-impl TimedSubtitle for SubRipSubtitle {}
+impl TimedSubtitle for SubRipSubtitle {
+    /// Split the subtitle at the given moment, keeping events before `at` and returning a new
+    /// subtitle containing the events after `at`.
+    ///
+    /// Events which start before and end after `at` are split into two.
+    /// Both subtitles are renumbered starting from 1.
+    fn split_at(&mut self, at: Moment) -> Self {
+        let mut tail = split_events_at(self, at);
+        self.renumber();
+        tail.renumber();
+
+        tail
+    }
+}
 
 impl Subtitle for SubRipSubtitle {
     type Event = SubRipEvent;
@@ -82,18 +193,14 @@ impl Subtitle for SubRipSubtitle {
         path: impl AsRef<Path>,
         encoding: Option<&'static Encoding>,
     ) -> Result<Self, Error> {
-        // Try to detect encoding quickly by only looking at first few lines of the file
-        // If an error results, then try detecting encoding using the entire file
-        // TODO: look at whether this is worth it (benchmark encoding detection using very large file)
-        let mut enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), Some(30)).ok());
-        let mut result = Self::try_from_path_with_encoding(path.as_ref(), enc);
-
-        if encoding.is_none() && result.is_err() {
-            enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), None).ok());
-            result = Self::try_from_path_with_encoding(path.as_ref(), enc);
-        }
+        let text = read_file_with_encoding(path.as_ref(), encoding, Some(30))?;
+        let reader = BufReader::new(text.as_bytes());
 
-        result
+        Ok(parse_srt(reader))
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        parse_from_reader(reader, |reader| parse_srt(BufReader::new(reader)))
     }
 
     fn events(&self) -> &[SubRipEvent] {
@@ -105,6 +212,12 @@ impl Subtitle for SubRipSubtitle {
     }
 }
 
+impl RetainEvents for SubRipSubtitle {
+    fn retain_events(&mut self, f: impl FnMut(&Self::Event) -> bool) {
+        self.events.retain(f);
+    }
+}
+
 impl Display for SubRipSubtitle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for (i, event) in self.events.iter().enumerate() {
@@ -115,6 +228,16 @@ impl Display for SubRipSubtitle {
     }
 }
 
+impl Display for SubRipCoordinates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "X1:{} X2:{} Y1:{} Y2:{}",
+            self.x1, self.x2, self.y1, self.y2
+        )
+    }
+}
+
 impl FromStr for SubRipSubtitle {
     type Err = Error;
 
@@ -142,7 +265,7 @@ impl From<&AssSubtitle> for SubRipSubtitle {
                     let mut text = dialogue.text.replace("\\N", "\n");
                     if let Ok((_, separated)) = split_formatting_tags(text.as_str()) {
                         if let Ok((_, converted)) = ass_to_srt_formatting(separated.as_str()) {
-                            text = converted;
+                            text = reopen_tags_across_lines(converted.as_str());
                         }
                     }
 
@@ -159,6 +282,50 @@ impl From<&AssSubtitle> for SubRipSubtitle {
     }
 }
 
+impl TryConvert<SubRipSubtitle> for AssSubtitle {
+    /// Convert to .srt format, failing if any embedded fonts or graphics, or if events use any styles
+    /// whose attributes differ from those of [`AssStyle::default`].
+    ///
+    /// Styles are found using [`AssSubtitle::event_style`].
+    fn try_convert(&self) -> Result<SubRipSubtitle, ConversionLoss> {
+        let mut styles: Vec<String> = Vec::new();
+        for style in self
+            .events()
+            .iter()
+            .filter_map(|event| self.event_style(event))
+        {
+            let is_default = *style
+                == AssStyle {
+                    name: style.name.clone(),
+                    ..AssStyle::default()
+                };
+            if !is_default && !styles.contains(&style.name) {
+                styles.push(style.name.clone());
+            }
+        }
+
+        let loss = ConversionLoss {
+            styles,
+            fonts: self
+                .fonts()
+                .iter()
+                .map(|font| font.fontname.clone())
+                .collect(),
+            graphics: self
+                .graphics()
+                .iter()
+                .map(|graphic| graphic.filename.clone())
+                .collect(),
+        };
+
+        if loss.is_empty() {
+            Ok(SubRipSubtitle::from(self))
+        } else {
+            Err(loss)
+        }
+    }
+}
+
 impl From<&TimedMicroDvdSubtitle> for SubRipSubtitle {
     fn from(value: &TimedMicroDvdSubtitle) -> Self {
         Self {
@@ -168,7 +335,7 @@ impl From<&TimedMicroDvdSubtitle> for SubRipSubtitle {
                 .enumerate()
                 .map(|(i, line)| SubRipEvent {
                     line_number: i + 1,
-                    text: line.text.replace('|', "\n"),
+                    text: mdvd_to_srt_formatting(line.text.as_str()),
                     start: line.start,
                     end: line.end,
                     coordinates: None,
@@ -195,7 +362,7 @@ impl From<&SsaSubtitle> for SubRipSubtitle {
                     let mut text = dialogue.text.replace("\\N", "\n");
                     if let Ok((_, separated)) = split_formatting_tags(text.as_str()) {
                         if let Ok((_, converted)) = ssa_to_srt_formatting(separated.as_str()) {
-                            text = converted;
+                            text = reopen_tags_across_lines(converted.as_str());
                         }
                     }
                     SubRipEvent {
@@ -297,6 +464,38 @@ impl From<TimedSubtitleFile> for SubRipSubtitle {
     }
 }
 
+impl SubRipEvent {
+    /// Remove all opening and closing tags with the given name from the event text,
+    /// leaving any other formatting tags intact.
+    ///
+    /// Both HTML style tags (`<font color="#ffffff">`, `</font>`) and bracket tags (`{b}`, `{/b}`) are removed.
+    /// Tag names are matched case-insensitively.
+    pub fn remove_tag(&mut self, tag_name: &str) {
+        if let Ok((_, stripped)) = strip_srt_tag(self.text.as_str(), tag_name) {
+            self.text = stripped;
+        }
+    }
+
+    /// Parse the coordinates of the event.
+    ///
+    /// Returns `None` if the event has no coordinates, or if they are malformed or incomplete.
+    #[must_use]
+    pub fn parsed_coordinates(&self) -> Option<SubRipCoordinates> {
+        self.coordinates.as_deref().and_then(parse_coordinates)
+    }
+
+    /// Replace the coordinates of the event with the given coordinates, written in the canonical form
+    pub fn set_coordinates(&mut self, coordinates: SubRipCoordinates) {
+        self.coordinates = Some(coordinates.to_string());
+    }
+}
+
+impl IdentifiableEvent for SubRipEvent {
+    fn identifier(&self) -> Option<String> {
+        Some(self.line_number.to_string())
+    }
+}
+
 impl TextEvent for SubRipEvent {
     fn unformatted_text(&self) -> Cow<'_, String> {
         let Ok((_, stripped)) = strip_srt_formatting(self.text.as_str()) else {
@@ -338,6 +537,8 @@ impl TimedEventInterface for SubRipEvent {
 }
 
 impl Display for SubRipEvent {
+    /// Events with empty text are written with an empty line after the timing line,
+    /// which is read back as an event with empty text.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
@@ -2,4 +2,4 @@ pub(crate) mod convert;
 mod data;
 pub(crate) mod parse;
 
-pub use data::{SubRipEvent, SubRipSubtitle};
+pub use data::{SubRipCoordinates, SubRipEvent, SubRipSubtitle};
@@ -1,21 +1,21 @@
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufReader, Read};
 
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_until, take_while1},
     character::complete::{char, i64, line_ending, multispace0, space0, u32},
-    combinator::{eof, map, opt, rest, verify},
+    combinator::{all_consuming, eof, map, opt, recognize, rest, verify},
     multi::{many_till, separated_list1},
     sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     IResult, Parser,
 };
 
 use crate::{
-    parsing::{bracket_tag, discard, html_tag, take_until_end_of_block},
+    parsing::{bracket_tag, discard, html_tag, lines, take_until_end_of_block},
     Moment, SubRipSubtitle,
 };
 
-use super::SubRipEvent;
+use super::{SubRipCoordinates, SubRipEvent};
 
 #[derive(Debug)]
 pub(crate) enum SubRipBlock {
@@ -23,7 +23,7 @@ pub(crate) enum SubRipBlock {
     LineContinuation(String),
 }
 
-fn parse_timestamp(input: &str) -> IResult<&str, Moment> {
+pub(crate) fn parse_timestamp(input: &str) -> IResult<&str, Moment> {
     map(
         delimited(
             space0,
@@ -48,6 +48,28 @@ fn parse_timing(input: &str) -> IResult<&str, ((Moment, Moment), Option<&str>)>
     .parse(input)
 }
 
+/// Parse extended SubRip coordinates (`X1:100 X2:600 Y1:500 Y2:550`), which may be given in any order.
+///
+/// Returns `None` unless all four coordinates are present exactly once and there is nothing else in the input.
+pub(crate) fn parse_coordinates(input: &str) -> Option<SubRipCoordinates> {
+    let mut values = [None; 4];
+    for part in input.split_whitespace() {
+        let (key, value) = part.split_once(':')?;
+        let index = ["X1", "X2", "Y1", "Y2"]
+            .iter()
+            .position(|name| key.eq_ignore_ascii_case(name))?;
+        if values[index].replace(value.parse::<i64>().ok()?).is_some() {
+            return None;
+        }
+    }
+
+    let [Some(x1), Some(x2), Some(y1), Some(y2)] = values else {
+        return None;
+    };
+
+    Some(SubRipCoordinates { x1, x2, y1, y2 })
+}
+
 fn parse_line_number(input: &str) -> IResult<&str, u32> {
     terminated(u32, line_ending).parse(input)
 }
@@ -64,7 +86,10 @@ pub(crate) fn parse_new_line(input: &str) -> IResult<&str, SubRipBlock> {
                 text,
                 start,
                 end,
-                coordinates: coordinates.map(std::string::ToString::to_string),
+                coordinates: coordinates
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(std::string::ToString::to_string),
             })
         },
     )
@@ -79,6 +104,26 @@ fn parse_continuation(input: &str) -> IResult<&str, SubRipBlock> {
     .parse(input)
 }
 
+/// Recognizes the start of a new event which was cut off before its text, e.g. at the end of a
+/// truncated file.
+///
+/// Returns the line number, and whether any part of a timing line follows it.
+fn parse_truncated_header(input: &str) -> IResult<&str, (u32, bool)> {
+    map(
+        all_consuming(tuple((
+            space0,
+            u32,
+            opt(pair(
+                line_ending,
+                take_while1(|c: char| c.is_ascii_digit() || ":,.-> ".contains(c)),
+            )),
+            multispace0,
+        ))),
+        |(_, line_number, timing, _)| (line_number, timing.is_some()),
+    )
+    .parse(input)
+}
+
 fn parse_block(input: &str) -> IResult<&str, SubRipBlock> {
     alt((parse_new_line, parse_continuation)).parse(input)
 }
@@ -87,8 +132,21 @@ pub(crate) fn parse_blocks(input: &str) -> IResult<&str, Vec<SubRipBlock>> {
     separated_list1(tag("\n\n"), parse_block).parse(input)
 }
 
+/// Whether a block at the end of the input is the start of an event which was cut off.
+///
+/// A bare number without any timing line is only treated as such if it is the line number expected
+/// for the next event, since it may otherwise be text belonging to the previous event (e.g. a year).
+fn is_truncated_header(content: &str, previous: Option<&SubRipEvent>) -> bool {
+    let Ok((_, (line_number, has_timing))) = parse_truncated_header(content) else {
+        return false;
+    };
+    let expected = previous.map_or(1, |event| event.line_number + 1);
+
+    has_timing || line_number as usize == expected
+}
+
 pub(crate) fn parse_srt<T: Read>(reader: BufReader<T>) -> SubRipSubtitle {
-    let mut lines = reader.lines();
+    let mut lines = lines(reader);
 
     let mut queue = String::new();
     let mut events = Vec::new();
@@ -118,6 +176,10 @@ pub(crate) fn parse_srt<T: Read>(reader: BufReader<T>) -> SubRipSubtitle {
                     events.push(line);
                 }
                 SubRipBlock::LineContinuation(content) => {
+                    if !is_streaming && is_truncated_header(content.as_str(), events.last()) {
+                        // Incomplete event at the end of the input, discard it
+                        continue;
+                    }
                     if let Some(line) = events.last_mut() {
                         line.text.push('\n');
                         line.text.push('\n');
@@ -133,6 +195,47 @@ pub(crate) fn parse_srt<T: Read>(reader: BufReader<T>) -> SubRipSubtitle {
     SubRipSubtitle::from_events(events)
 }
 
+/// Find the first line which does not fit the structure of a SubRip file, returning its line
+/// number (starting from 1) and a description of the problem.
+pub(crate) fn find_invalid_line(input: &str) -> Option<(usize, String)> {
+    let mut lines = lines(input.as_bytes())
+        .map(Result::unwrap_or_default)
+        .enumerate()
+        .map(|(index, line)| (index + 1, line));
+
+    while let Some((number, line)) = lines.next() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.trim().parse::<u32>().is_err() {
+            return Some((number, format!("expected event number, found {line:?}")));
+        }
+
+        match lines.next() {
+            Some((number, line)) => {
+                if separated_pair(parse_timestamp, tag("-->"), parse_timestamp)
+                    .parse(line.as_str())
+                    .is_err()
+                {
+                    return Some((number, format!("expected timing line, found {line:?}")));
+                }
+            }
+            None => {
+                return Some((number, "event is missing timing line".to_string()));
+            }
+        }
+
+        // Skip over the text of the event
+        for (_, line) in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+        }
+    }
+
+    None
+}
+
 pub(crate) fn strip_srt_formatting(input: &str) -> IResult<&str, String> {
     map(
         many_till(
@@ -148,3 +251,29 @@ pub(crate) fn strip_srt_formatting(input: &str) -> IResult<&str, String> {
     )
     .parse(input)
 }
+
+fn is_named_tag(content: &str, name: &str) -> bool {
+    content
+        .trim_start_matches('/')
+        .split_whitespace()
+        .next()
+        .is_some_and(|tag_name| tag_name.eq_ignore_ascii_case(name))
+}
+
+pub(crate) fn strip_srt_tag<'a>(input: &'a str, name: &str) -> IResult<&'a str, String> {
+    map(
+        many_till(
+            alt((
+                discard(verify(html_tag, |s: &str| is_named_tag(s, name))),
+                discard(verify(bracket_tag, |s: &str| is_named_tag(s, name))),
+                recognize(html_tag),
+                recognize(bracket_tag),
+                take_while1(|c| c != '<' && c != '{'),
+                rest,
+            )),
+            eof,
+        ),
+        |(s, _)| s.concat(),
+    )
+    .parse(input)
+}
@@ -1,25 +1,75 @@
-use std::{borrow::Cow, fmt::Display, fs::File, io::BufReader, path::Path, str::FromStr};
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    io::{BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
 
-use buildstructor::Builder;
+use buildstructor::{buildstructor, Builder};
 use encoding_rs::Encoding;
-use encoding_rs_io::DecodeReaderBytesBuilder;
 
 use crate::{
-    encoding::detect_file_encoding,
+    encoding::{parse_from_reader, read_file_with_encoding},
     errors::Error,
+    microdvd::convert::mdvd_to_substation_formatting,
     plain::PlainSubtitle,
+    sdh::remove_sdh_annotations,
     subrip::convert::srt_to_ass_formatting,
-    substation::common::data::{SubStationEventKind, SubStationFont, SubStationGraphic},
-    traits::TimedSubtitle,
-    webvtt::convert::vtt_to_ass_formatting,
-    Moment, SsaSubtitle, SubRipSubtitle, Subtitle, TextEvent, TextEventInterface, TextSubtitle,
-    TimedEvent, TimedEventInterface, TimedMicroDvdSubtitle, TimedSubtitleFile, WebVttSubtitle,
+    substation::common::data::{
+        field_order, format_reverse_bool, style_names_match, values_from_standard_order,
+        write_format_line, SubStationEventKind, SubStationFont, SubStationGraphic,
+    },
+    traits::{mark_rtl_lines, RetainEvents, TimedSubtitle},
+    webvtt::{
+        convert::{voice_spans, vtt_settings_to_ass_alignment, vtt_to_ass_formatting},
+        WebVttCue,
+    },
+    IdentifiableEvent, Moment, SsaSubtitle, SubRipSubtitle, Subtitle, TextEvent,
+    TextEventInterface, TextSubtitle, TimeDelta, TimedEvent, TimedEventInterface,
+    TimedMicroDvdSubtitle, TimedSubtitleFile, WebVttSubtitle,
+};
+
+use super::{
+    convert::strip_formatting_tags,
+    parse::{parse_ass, parse_ass_strict, parse_mkv_block},
+    tags::{tag_spans, AssTag, AssTagSpan},
 };
 
-use super::{convert::strip_formatting_tags, parse::parse_ass};
+/// Fields of a style line, in the standard order
+pub(crate) const ASS_STYLE_FIELDS: [&str; 23] = [
+    "Name",
+    "Fontname",
+    "Fontsize",
+    "PrimaryColour",
+    "SecondaryColour",
+    "OutlineColour",
+    "BackColour",
+    "Bold",
+    "Italic",
+    "Underline",
+    "StrikeOut",
+    "ScaleX",
+    "ScaleY",
+    "Spacing",
+    "Angle",
+    "BorderStyle",
+    "Outline",
+    "Shadow",
+    "Alignment",
+    "MarginL",
+    "MarginR",
+    "MarginV",
+    "Encoding",
+];
+
+/// Fields of an event line, in the standard order
+pub(crate) const ASS_EVENT_FIELDS: [&str; 10] = [
+    "Layer", "Start", "End", "Style", "Actor", "MarginL", "MarginR", "MarginV", "Effect", "Text",
+];
 
 /// Advanced SubStation Alpha v4+ (.ass) subtitle
-#[derive(Debug, Builder)]
+#[derive(Clone, Debug)]
 pub struct AssSubtitle {
     /// Script info
     script_info: AssScriptInfo,
@@ -34,16 +84,28 @@ pub struct AssSubtitle {
     movies: Vec<AssEvent>,
     /// Command events
     commands: Vec<AssEvent>,
+    /// Comment events
+    comments: Vec<AssEvent>,
+    /// Index of the dialogue event which each comment is written before, so that comments keep
+    /// their position among dialogue. Equal to the number of dialogue events if written after all of them.
+    comment_positions: Vec<usize>,
     /// Styles
     styles: Vec<AssStyle>,
     /// Embedded font data
     fonts: Vec<SubStationFont>,
     /// Embedded graphics data
     graphics: Vec<SubStationGraphic>,
+    /// Fields of the styles `Format:` line, in the order they were given in the parsed file
+    styles_format: Vec<String>,
+    /// Fields of the `[Events]` `Format:` line, in the order they were given in the parsed file
+    events_format: Vec<String>,
+    /// Plaintext of dialogue events, if computed using `build_plaintext_cache`.
+    /// Not part of the builder, and cleared whenever dialogue may be modified.
+    plaintext_cache: Vec<String>,
 }
 
 /// Advanced Substation Alpha (.ass) event
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct AssEvent {
     /// Kind of event, for example dialogue
     pub kind: SubStationEventKind,
@@ -71,10 +133,24 @@ pub struct AssEvent {
     pub text: String,
 }
 
+/// How comment events are included as text by [`AssSubtitle::include_comments_as_text`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CommentPolicy {
+    /// Leave comments as they are, so that they are dropped when converting to another format
+    #[default]
+    Drop,
+    /// Include comments as dialogue with their text unchanged
+    Plain,
+    /// Include comments as dialogue with their text wrapped in braces, e.g. `{TL note}`
+    Braces,
+    /// Include comments as dialogue with their text wrapped in square brackets, e.g. `[TL note]`
+    Brackets,
+}
+
 /// Information for the `[ScriptInfo]` section of an Advanced SubStation Alpha (.ass) subtitle.
 ///
 /// It should always be the first thing shown in an .ass format subtitle.
-#[derive(Debug, Builder)]
+#[derive(Clone, Debug, Builder)]
 pub struct AssScriptInfo {
     /// Title/description for the subtitle
     pub title: Option<String>,
@@ -107,10 +183,13 @@ pub struct AssScriptInfo {
     pub timer: Option<String>,
     /// Defines wrapping rules for text
     pub wrap_style: Option<String>,
+    /// Other properties which are not recognised, as pairs of keys and values in the order they were given,
+    /// e.g. `ScaledBorderAndShadow: yes`
+    pub extra: Vec<(String, String)>,
 }
 
 /// Style in a .ass file
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct AssStyle {
     /// Name of style
     pub name: String,
@@ -160,7 +239,163 @@ pub struct AssStyle {
     pub encoding: i64,
 }
 
+#[buildstructor]
 impl AssSubtitle {
+    /// Create a subtitle from its parts, for use by [`AssSubtitle::builder`] so that the
+    /// comment positions and plaintext cache are not part of the builder
+    #[builder(visibility = "pub")]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        script_info: AssScriptInfo,
+        dialogue: Vec<AssEvent>,
+        pictures: Vec<AssEvent>,
+        sounds: Vec<AssEvent>,
+        movies: Vec<AssEvent>,
+        commands: Vec<AssEvent>,
+        comments: Vec<AssEvent>,
+        styles: Vec<AssStyle>,
+        fonts: Vec<SubStationFont>,
+        graphics: Vec<SubStationGraphic>,
+        styles_format: Vec<String>,
+        events_format: Vec<String>,
+    ) -> Self {
+        Self {
+            script_info,
+            comment_positions: vec![dialogue.len(); comments.len()],
+            dialogue,
+            pictures,
+            sounds,
+            movies,
+            commands,
+            comments,
+            styles,
+            fonts,
+            graphics,
+            styles_format,
+            events_format,
+            plaintext_cache: Vec::new(),
+        }
+    }
+
+    /// Get mutable access to the dialogue events, clearing the plaintext cache as they may be modified
+    fn dialogue_mut(&mut self) -> &mut Vec<AssEvent> {
+        self.plaintext_cache.clear();
+        &mut self.dialogue
+    }
+
+    /// Set the index of the dialogue event which each comment was written before
+    pub(crate) fn with_comment_positions(mut self, positions: Vec<usize>) -> Self {
+        self.comment_positions = positions;
+        self
+    }
+
+    /// Reorder dialogue events, keeping only those at the given indices, in the given order.
+    ///
+    /// Comments stay before the same dialogue event, or move to the next remaining event if it was removed.
+    fn reorder_dialogue(&mut self, order: &[usize]) {
+        let mut new_positions = vec![None; self.dialogue.len()];
+        for (new, old) in order.iter().enumerate() {
+            new_positions[*old] = Some(new);
+        }
+        for position in &mut self.comment_positions {
+            *position = new_positions
+                .get(*position..)
+                .and_then(|following| following.iter().find_map(|new| *new))
+                .unwrap_or(order.len());
+        }
+
+        let mut old: Vec<Option<AssEvent>> = self.dialogue_mut().drain(..).map(Some).collect();
+        self.dialogue = order
+            .iter()
+            .filter_map(|index| old[*index].take())
+            .collect();
+    }
+
+    /// Retain only the events of every kind for which the given predicate returns `true`
+    fn retain_all_events(&mut self, mut f: impl FnMut(&AssEvent) -> bool) {
+        self.retain_events(&mut f);
+        for events in [
+            &mut self.pictures,
+            &mut self.sounds,
+            &mut self.movies,
+            &mut self.commands,
+        ] {
+            events.retain(&mut f);
+        }
+
+        let kept: Vec<bool> = self.comments.iter().map(&mut f).collect();
+        let mut kept_comments = kept.iter();
+        self.comments
+            .retain(|_| kept_comments.next().copied().unwrap_or(true));
+        let mut kept_positions = kept.iter();
+        self.comment_positions
+            .retain(|_| kept_positions.next().copied().unwrap_or(true));
+    }
+
+    /// Events in the order they are written, with each comment before the dialogue event it precedes
+    fn events_in_written_order(&self) -> Vec<&AssEvent> {
+        let mut comments: Vec<(usize, &AssEvent)> = self
+            .comments
+            .iter()
+            .enumerate()
+            .map(|(index, comment)| {
+                let position = self.comment_positions.get(index).copied();
+                (position.unwrap_or(usize::MAX), comment)
+            })
+            .collect();
+        comments.sort_by_key(|(position, _)| *position);
+        let mut comments = comments.into_iter().peekable();
+
+        let mut events = Vec::new();
+        for (index, event) in self.dialogue.iter().enumerate() {
+            while let Some((_, comment)) = comments.next_if(|(position, _)| *position <= index) {
+                events.push(comment);
+            }
+            events.push(event);
+        }
+        events.extend(comments.map(|(_, comment)| comment));
+        events.extend(
+            self.pictures
+                .iter()
+                .chain(&self.sounds)
+                .chain(&self.movies)
+                .chain(&self.commands),
+        );
+
+        events
+    }
+
+    /// Parse subtitles strictly, failing instead of skipping over lines which cannot be parsed.
+    ///
+    /// Blank lines, comments, and lines in unsupported sections are still skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] identifying the first line which could not be parsed.
+    pub fn from_str_strict(s: &str) -> Result<Self, Error> {
+        let s = s.strip_prefix('\u{feff}').unwrap_or(s);
+
+        parse_ass_strict(BufReader::new(s.as_bytes()))
+    }
+
+    /// Get fields of the `Format:` line of the `[Events]` section, as given in the parsed file.
+    ///
+    /// Empty if the subtitle was not parsed from a file with such a line.
+    /// When written, events use this field order if it is a reordering of the standard fields,
+    /// otherwise the standard order is used.
+    #[must_use]
+    pub fn events_format_fields(&self) -> &[String] {
+        self.events_format.as_slice()
+    }
+
+    /// Get fields of the `Format:` line of the styles section, as given in the parsed file.
+    ///
+    /// See [`AssSubtitle::events_format_fields`] for how these are used when writing.
+    #[must_use]
+    pub fn styles_format_fields(&self) -> &[String] {
+        self.styles_format.as_slice()
+    }
+
     /// Get list of picture events as a slice
     #[must_use]
     pub fn pictures(&self) -> &[AssEvent] {
@@ -249,6 +484,162 @@ impl AssSubtitle {
         self.commands.get_mut(index)
     }
 
+    /// Get list of comment events as a slice
+    #[must_use]
+    pub fn comments(&self) -> &[AssEvent] {
+        self.comments.as_slice()
+    }
+
+    /// Get list of comment events as a mutable slice
+    pub fn comments_mut(&mut self) -> &mut [AssEvent] {
+        self.comments.as_mut_slice()
+    }
+
+    /// Get comment event at given index
+    #[must_use]
+    pub fn comment(&self, index: usize) -> Option<&AssEvent> {
+        self.comments.get(index)
+    }
+
+    /// Get mutable comment event at given index
+    pub fn comment_mut(&mut self, index: usize) -> Option<&mut AssEvent> {
+        self.comments.get_mut(index)
+    }
+
+    /// Turn comment events into dialogue events in their original position, so that they are kept
+    /// as normal lines when converting to another format. With [`CommentPolicy::Drop`], which is the
+    /// default, the subtitle is left unchanged and comments are dropped by conversions as usual.
+    ///
+    /// Note that text wrapped in braces is hidden when written as .ass or .ssa,
+    /// and removed when converting to .sub.
+    pub fn include_comments_as_text(&mut self, policy: CommentPolicy) {
+        let (open, close) = match policy {
+            CommentPolicy::Drop => return,
+            CommentPolicy::Plain => ("", ""),
+            CommentPolicy::Braces => ("{", "}"),
+            CommentPolicy::Brackets => ("[", "]"),
+        };
+
+        let dialogue = self
+            .events_in_written_order()
+            .into_iter()
+            .filter_map(|event| match event.kind {
+                SubStationEventKind::Dialogue => Some(event.clone()),
+                SubStationEventKind::Comment => Some(AssEvent {
+                    kind: SubStationEventKind::Dialogue,
+                    text: format!("{open}{}{close}", event.text),
+                    ..event.clone()
+                }),
+                _ => None,
+            })
+            .collect();
+
+        *self.dialogue_mut() = dialogue;
+        self.comments.clear();
+        self.comment_positions.clear();
+    }
+
+    /// Iterate over events of every kind: dialogue, pictures, sounds, movies, commands, and then comments.
+    ///
+    /// When written, comments are instead kept in their original position among the dialogue.
+    pub fn all_events(&self) -> impl Iterator<Item = &AssEvent> {
+        self.dialogue
+            .iter()
+            .chain(&self.pictures)
+            .chain(&self.sounds)
+            .chain(&self.movies)
+            .chain(&self.commands)
+            .chain(&self.comments)
+    }
+
+    /// Iterate mutably over events of every kind: dialogue, pictures, sounds, movies, commands, and then comments
+    pub fn all_events_mut(&mut self) -> impl Iterator<Item = &mut AssEvent> {
+        self.plaintext_cache.clear();
+        self.dialogue
+            .iter_mut()
+            .chain(&mut self.pictures)
+            .chain(&mut self.sounds)
+            .chain(&mut self.movies)
+            .chain(&mut self.commands)
+            .chain(&mut self.comments)
+    }
+
+    /// Build a subtitle from the parts of an ASS track in a Matroska file.
+    ///
+    /// `codec_private` contains the header of the subtitle, including script info and styles.
+    /// Each sample is a dialogue block of the form
+    /// `ReadOrder,Layer,Style,Name,MarginL,MarginR,MarginV,Effect,Text`, along with its start and end time.
+    /// Dialogue events are ordered by their read order, and malformed blocks are skipped.
+    #[must_use]
+    pub fn from_mkv_parts(codec_private: &str, samples: &[(Moment, Moment, &str)]) -> Self {
+        let mut subtitle = parse_ass(BufReader::new(codec_private.as_bytes()));
+
+        let mut blocks: Vec<(i64, AssEvent)> = samples
+            .iter()
+            .filter_map(|(start, end, block)| {
+                parse_mkv_block(block, *start, *end)
+                    .ok()
+                    .map(|(_, block)| block)
+            })
+            .collect();
+        blocks.sort_by_key(|(read_order, _)| *read_order);
+        subtitle
+            .dialogue
+            .extend(blocks.into_iter().map(|(_, event)| event));
+
+        subtitle
+    }
+
+    /// Convert WebVTT (.vtt) format subtitle to .ass format, splitting each cue into one event per
+    /// voice span (`<v Name>`) and setting the name of the event to the speaker.
+    ///
+    /// Events split from the same cue share its timing. Text outside of any voice span becomes an
+    /// event without a name. Otherwise, conversion is the same as the [`From`] implementation.
+    #[must_use]
+    pub fn from_webvtt_by_voice(value: &WebVttSubtitle) -> Self {
+        AssSubtitle::builder()
+            .script_info(AssScriptInfo::builder().and_title(value.header()).build())
+            .dialogue(
+                value
+                    .events()
+                    .iter()
+                    .flat_map(|cue| {
+                        let spans = voice_spans(cue.text.as_str(), true);
+                        if spans.is_empty() {
+                            return vec![vtt_cue_to_ass_event(cue, cue.text.as_str(), None)];
+                        }
+                        spans
+                            .into_iter()
+                            .map(|(name, text)| vtt_cue_to_ass_event(cue, text.as_str(), name))
+                            .collect()
+                    })
+                    .collect(),
+            )
+            .build()
+    }
+
+    /// Compute and store the plaintext of every dialogue event, so that it can be retrieved
+    /// with [`AssSubtitle::cached_plaintext`] without stripping formatting tags again.
+    ///
+    /// The cache is cleared whenever dialogue events are mutably accessed, for example with
+    /// [`Subtitle::events_mut`], [`Subtitle::event_mut`], [`RetainEvents::retain_events`](crate::RetainEvents::retain_events) or
+    /// [`AssSubtitle::all_events_mut`], and so also by sorting or shifting events.
+    pub fn build_plaintext_cache(&mut self) {
+        self.plaintext_cache = self
+            .dialogue
+            .iter()
+            .map(|event| event.as_plaintext().into_owned())
+            .collect();
+    }
+
+    /// Get the cached plaintext of the dialogue event at the given index.
+    ///
+    /// Returns `None` if the cache has not been built, or has been cleared since.
+    #[must_use]
+    pub fn cached_plaintext(&self, index: usize) -> Option<&str> {
+        self.plaintext_cache.get(index).map(String::as_str)
+    }
+
     /// Get script info struct
     #[must_use]
     pub fn script_info(&self) -> &AssScriptInfo {
@@ -271,6 +662,116 @@ impl AssSubtitle {
         self.styles.as_mut_slice()
     }
 
+    /// Get style with the given name.
+    ///
+    /// Names are matched case-insensitively, and a leading `*` in either name is ignored,
+    /// so `*default` will match a style named `Default`.
+    #[must_use]
+    pub fn style_by_name(&self, name: &str) -> Option<&AssStyle> {
+        self.styles
+            .iter()
+            .find(|style| style_names_match(style.name.as_str(), name))
+    }
+
+    /// Get mutable style with the given name, matched in the same way as [`AssSubtitle::style_by_name`]
+    pub fn style_by_name_mut(&mut self, name: &str) -> Option<&mut AssStyle> {
+        self.styles
+            .iter_mut()
+            .find(|style| style_names_match(style.name.as_str(), name))
+    }
+
+    /// Get the style used by the given event.
+    ///
+    /// If the event has no style, or its style does not exist, the style named `Default` is used instead.
+    #[must_use]
+    pub fn event_style(&self, event: &AssEvent) -> Option<&AssStyle> {
+        event
+            .style
+            .as_deref()
+            .and_then(|name| self.style_by_name(name))
+            .or_else(|| self.style_by_name("Default"))
+    }
+
+    /// Get the style of the dialogue event at the given index, with the override tags at the start
+    /// of its text applied, i.e. the style that the event is actually displayed with.
+    ///
+    /// The style is found using [`AssSubtitle::event_style`]. Only the `\b`, `\i`, `\u`, `\fs`,
+    /// and `\c` (or `\1c`) tags are applied, and only those in override blocks before any text.
+    ///
+    /// Returns `None` if there is no event at the given index, or no style could be found for it.
+    #[must_use]
+    pub fn effective_style(&self, event_index: usize) -> Option<AssStyle> {
+        let event = self.dialogue.get(event_index)?;
+        let mut style = self.event_style(event)?.clone();
+        apply_leading_overrides(&mut style, event.text.as_str());
+
+        Some(style)
+    }
+
+    /// Add a style to the end of the list of styles
+    pub fn add_style(&mut self, style: AssStyle) {
+        self.styles.push(style);
+    }
+
+    /// Remove all styles with the given name.
+    ///
+    /// Names are matched in the same way as [`AssSubtitle::style_by_name`]. Events which use the removed
+    /// style will have their style cleared.
+    pub fn remove_style(&mut self, name: &str) {
+        self.styles
+            .retain(|style| !style_names_match(style.name.as_str(), name));
+
+        for event in self.all_events_mut() {
+            if event
+                .style
+                .as_deref()
+                .is_some_and(|style| style_names_match(style, name))
+            {
+                event.style = None;
+            }
+        }
+    }
+
+    /// Merge styles which are identical apart from their names.
+    ///
+    /// The first of each set of identical styles is kept, and events using any of the removed
+    /// duplicates are changed to use the kept style instead.
+    pub fn dedup_styles(&mut self) {
+        let mut kept: Vec<AssStyle> = Vec::new();
+        let mut renames = Vec::new();
+
+        for style in self.styles.drain(..) {
+            let unnamed = AssStyle {
+                name: String::new(),
+                ..style.clone()
+            };
+            let duplicate_of = kept.iter().find(|existing| {
+                AssStyle {
+                    name: String::new(),
+                    ..(*existing).clone()
+                } == unnamed
+            });
+
+            match duplicate_of {
+                Some(existing) => renames.push((style.name, existing.name.clone())),
+                None => kept.push(style),
+            }
+        }
+        self.styles = kept;
+
+        for event in self.all_events_mut() {
+            let Some(style) = event.style.as_deref() else {
+                continue;
+            };
+            if let Some((_, kept_name)) = renames
+                .iter()
+                .find(|(duplicate, _)| style_names_match(style, duplicate))
+            {
+                event.style = Some(kept_name.clone());
+            }
+        }
+    }
+
     /// Get list of fonts as a slice
     #[must_use]
     pub fn fonts(&self) -> &[SubStationFont] {
@@ -292,19 +793,6 @@ impl AssSubtitle {
     pub fn graphics_mut(&mut self) -> &mut [SubStationGraphic] {
         self.graphics.as_mut_slice()
     }
-
-    fn open_file_with_encoding(
-        path: &Path,
-        encoding: Option<&'static Encoding>,
-    ) -> Result<Self, Error> {
-        let file = File::open(path)?;
-        let transcoded = DecodeReaderBytesBuilder::new()
-            .encoding(encoding)
-            .build(file);
-        let reader = BufReader::new(transcoded);
-
-        Ok(parse_ass(reader))
-    }
 }
 
 impl Subtitle for AssSubtitle {
@@ -314,15 +802,14 @@ impl Subtitle for AssSubtitle {
         path: impl AsRef<Path>,
         encoding: Option<&'static Encoding>,
     ) -> Result<Self, Error> {
-        let mut enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), Some(40)).ok());
-        let mut result = Self::open_file_with_encoding(path.as_ref(), enc);
+        let text = read_file_with_encoding(path.as_ref(), encoding, Some(40))?;
+        let reader = BufReader::new(text.as_bytes());
 
-        if encoding.is_none() && result.is_err() {
-            enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), None).ok());
-            result = Self::open_file_with_encoding(path.as_ref(), enc);
-        }
+        Ok(parse_ass(reader))
+    }
 
-        result
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        parse_from_reader(reader, |reader| parse_ass(BufReader::new(reader)))
     }
 
     fn events(&self) -> &[AssEvent] {
@@ -330,7 +817,19 @@ impl Subtitle for AssSubtitle {
     }
 
     fn events_mut(&mut self) -> &mut [AssEvent] {
-        self.dialogue.as_mut_slice()
+        self.dialogue_mut().as_mut_slice()
+    }
+}
+
+impl RetainEvents for AssSubtitle {
+    /// Retain only the dialogue events for which the given predicate returns `true`.
+    ///
+    /// Comments written before a removed event are moved before the next remaining event.
+    fn retain_events(&mut self, mut f: impl FnMut(&Self::Event) -> bool) {
+        let kept: Vec<usize> = (0..self.dialogue.len())
+            .filter(|index| f(&self.dialogue[*index]))
+            .collect();
+        self.reorder_dialogue(&kept);
     }
 }
 
@@ -344,52 +843,95 @@ impl TextSubtitle for AssSubtitle {
     }
 }
 
-impl TimedSubtitle for AssSubtitle {}
+impl TimedSubtitle for AssSubtitle {
+    /// Shift all events by the given amount of time, in milliseconds.
+    ///
+    /// Unlike [`Subtitle::events`], this includes picture, sound, movie, command and comment events in addition to dialogue.
+    fn shift(&mut self, delta: TimeDelta) {
+        for event in self.all_events_mut() {
+            event.shift(delta);
+        }
+    }
+
+    /// Split the subtitle at the given moment, keeping events before `at` and returning a new
+    /// subtitle containing the events after `at`.
+    ///
+    /// Unlike [`Subtitle::events`], this includes picture, sound, movie, command and comment events in addition to dialogue.
+    /// Events which start before and end after `at` are split into two, as with the default implementation.
+    fn split_at(&mut self, at: Moment) -> Self {
+        let mut tail = self.clone();
+
+        self.retain_all_events(|event| event.start < at);
+        for event in self.all_events_mut() {
+            if event.end > at {
+                event.end = at;
+            }
+        }
+
+        tail.retain_all_events(|event| event.start >= at || event.end > at);
+        for event in tail.all_events_mut() {
+            if event.start < at {
+                event.start = at;
+            }
+        }
+
+        tail
+    }
+
+    /// Sort dialogue events in chronological order, by start time and then by end time.
+    ///
+    /// Comments stay before the same dialogue event when written.
+    fn sort_by_time(&mut self) {
+        let mut order: Vec<usize> = (0..self.dialogue.len()).collect();
+        order.sort_by_key(|index| (self.dialogue[*index].start, self.dialogue[*index].end));
+        self.reorder_dialogue(&order);
+    }
+}
 
 impl Display for AssSubtitle {
+    /// Write subtitle in .ass format.
+    ///
+    /// Lines are normalized rather than reproduced exactly as parsed. In particular, the keyword of
+    /// each style and event line is always written in title case followed by `: ` (e.g. `Dialogue: `),
+    /// even if the parsed file used a different case (e.g. `dialogue:`), which is accepted when parsing.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{}", self.script_info)?;
         writeln!(f)?;
         if !self.styles.is_empty() {
             writeln!(f, "[V4+ Styles]")?;
-            writeln!(f, "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding")?;
+            let order = field_order(&self.styles_format, &ASS_STYLE_FIELDS);
+            write_format_line(f, &self.styles_format, &ASS_STYLE_FIELDS, order.is_some())?;
             for style in &self.styles {
-                writeln!(f, "{style}")?;
+                writeln!(
+                    f,
+                    "{}",
+                    values_from_standard_order(&style.to_string(), order.as_deref())
+                )?;
             }
             writeln!(f)?;
         }
         if !self.fonts.is_empty() {
-            writeln!(f)?;
+            writeln!(f, "[Fonts]")?;
             for font in &self.fonts {
                 writeln!(f, "{font}")?;
             }
         }
         if !self.graphics.is_empty() {
-            writeln!(f)?;
+            writeln!(f, "[Graphics]")?;
             for graphic in &self.graphics {
                 writeln!(f, "{graphic}")?;
             }
         }
-        if !self.dialogue.is_empty() {
+        if !self.dialogue.is_empty() || !self.comments.is_empty() {
             writeln!(f, "[Events]")?;
-            writeln!(
-                f,
-                "Format: Layer, Start, End, Style, Actor, MarginL, MarginR, MarginV, Effect, Text"
-            )?;
-            for event in &self.dialogue {
-                writeln!(f, "{event}")?;
-            }
-            for event in &self.pictures {
-                writeln!(f, "{event}")?;
-            }
-            for event in &self.sounds {
-                writeln!(f, "{event}")?;
-            }
-            for event in &self.movies {
-                writeln!(f, "{event}")?;
-            }
-            for event in &self.commands {
-                writeln!(f, "{event}")?;
+            let order = field_order(&self.events_format, &ASS_EVENT_FIELDS);
+            write_format_line(f, &self.events_format, &ASS_EVENT_FIELDS, order.is_some())?;
+            for event in self.events_in_written_order() {
+                writeln!(
+                    f,
+                    "{}",
+                    values_from_standard_order(&event.to_string(), order.as_deref())
+                )?;
             }
         }
 
@@ -434,7 +976,7 @@ impl From<&TimedMicroDvdSubtitle> for AssSubtitle {
                         margin_r: 0,
                         margin_v: 0,
                         effect: None,
-                        text: line.text.replace('|', "\\N"),
+                        text: mdvd_to_substation_formatting(line.text.as_str()),
                     })
                     .collect(),
             )
@@ -511,6 +1053,7 @@ impl From<&WebVttSubtitle> for AssSubtitle {
     ///
     /// For each line, this will convert newlines into the appropriate representation,
     /// and it will also convert the basic HTML formatting tags (`<b>`, `<i>`, and `<u>`).
+    /// The `line` and `align` cue settings are converted to an `\an` alignment tag.
     ///
     /// All other tags and styles are discarded.
     fn from(value: &WebVttSubtitle) -> Self {
@@ -520,31 +1063,90 @@ impl From<&WebVttSubtitle> for AssSubtitle {
                 value
                     .events()
                     .iter()
-                    .map(|cue| {
-                        let mut text = cue.text.replace('\n', "\\N");
-                        if let Ok((_, converted)) = vtt_to_ass_formatting(text.as_str()) {
-                            text = converted;
-                        }
-                        AssEvent {
-                            kind: SubStationEventKind::Dialogue,
-                            layer: 0,
-                            start: cue.start,
-                            end: cue.end,
-                            style: None,
-                            name: None,
-                            margin_l: 0,
-                            margin_r: 0,
-                            margin_v: 0,
-                            effect: None,
-                            text,
-                        }
-                    })
+                    .map(|cue| vtt_cue_to_ass_event(cue, cue.text.as_str(), None))
                     .collect(),
             )
             .build()
     }
 }
 
+/// Apply the basic override tags (`\b`, `\i`, `\u`, `\fs`, `\c`) in the override blocks at the
+/// start of `text` to `style`. Unsupported or malformed tags are ignored.
+fn apply_leading_overrides(style: &mut AssStyle, text: &str) {
+    if !text.starts_with('{') {
+        return;
+    }
+    let Some(span) = tag_spans(text).into_iter().next() else {
+        return;
+    };
+
+    for tag in span.tags {
+        match tag {
+            AssTag::FontSize(size) => {
+                #[allow(clippy::cast_possible_truncation)]
+                let size = size.round() as i64;
+                style.fontsize = size;
+            }
+            AssTag::Colour { index: 1, colour } => {
+                if let Some(colour) = override_colour(style.primary_colour.as_str(), &colour) {
+                    style.primary_colour = colour;
+                }
+            }
+            // Either 0 or 1, or a font weight
+            AssTag::Bold(weight) => style.bold = weight == 1 || weight >= 700,
+            AssTag::Italic(italic) => style.italic = italic,
+            AssTag::Underline(underline) => style.underline = underline,
+            _ => {}
+        }
+    }
+}
+
+/// Convert an override tag colour (`&HBBGGRR&`) to a style colour (`&HAABBGGRR`),
+/// keeping the alpha of the style colour being replaced
+fn override_colour(current: &str, colour: &str) -> Option<String> {
+    let bgr = colour
+        .strip_prefix("&H")
+        .or_else(|| colour.strip_prefix("&h"))?
+        .trim_end_matches('&');
+    if bgr.is_empty() || bgr.len() > 6 || !bgr.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let alpha = current
+        .get(2..4)
+        .filter(|_| current.len() == 10)
+        .unwrap_or("00");
+
+    Some(format!("&H{alpha}{:0>6}", bgr.to_ascii_uppercase()))
+}
+
+/// Convert the given text of a WebVTT cue to an .ass event, using the timing and settings of the cue
+fn vtt_cue_to_ass_event(cue: &WebVttCue, text: &str, name: Option<String>) -> AssEvent {
+    let mut text = text.replace('\n', "\\N");
+    if let Ok((_, converted)) = vtt_to_ass_formatting(text.as_str()) {
+        text = converted;
+    }
+    let alignment = cue
+        .settings
+        .as_deref()
+        .map_or(2, vtt_settings_to_ass_alignment);
+    if alignment != 2 {
+        text.insert_str(0, format!("{{\\an{alignment}}}").as_str());
+    }
+    AssEvent {
+        kind: SubStationEventKind::Dialogue,
+        layer: 0,
+        start: cue.start,
+        end: cue.end,
+        style: None,
+        name,
+        margin_l: 0,
+        margin_r: 0,
+        margin_v: 0,
+        effect: None,
+        text,
+    }
+}
+
 impl From<TimedMicroDvdSubtitle> for AssSubtitle {
     fn from(value: TimedMicroDvdSubtitle) -> Self {
         Self::from(&value)
@@ -608,7 +1210,51 @@ impl From<PlainSubtitle> for AssSubtitle {
     }
 }
 
+impl AssEvent {
+    /// Split the text of the event into runs of text, each with the override tags directly before it.
+    ///
+    /// Tags apply from the start of their span until they are overridden, so e.g. karaoke timings can be
+    /// found by accumulating the [`AssTag::Karaoke`] durations of each span.
+    #[must_use]
+    pub fn tags(&self) -> Vec<AssTagSpan> {
+        tag_spans(self.text.as_str())
+    }
+
+    /// Set the left, right and vertical margins of the event.
+    ///
+    /// Negative margins are not meaningful, so they are clamped to 0.
+    pub fn set_margins(&mut self, left: i64, right: i64, vertical: i64) {
+        self.margin_l = left.max(0);
+        self.margin_r = right.max(0);
+        self.margin_v = vertical.max(0);
+    }
+
+    /// Reset all margins to 0, so that the margins of the event's style are used instead
+    pub fn reset_margins(&mut self) {
+        self.set_margins(0, 0, 0);
+    }
+
+    /// Set the layer of the event
+    pub fn set_layer(&mut self, layer: i64) {
+        self.layer = layer;
+    }
+}
+
+impl IdentifiableEvent for AssEvent {
+    fn identifier(&self) -> Option<String> {
+        None
+    }
+}
+
 impl TextEvent for AssEvent {
+    fn remove_sdh(&mut self) {
+        self.text = remove_sdh_annotations(self.text.as_str(), "\\N");
+    }
+
+    fn mark_rtl(&mut self) {
+        self.text = mark_rtl_lines(self.text.as_str(), "\\N");
+    }
+
     fn unformatted_text(&self) -> Cow<'_, String> {
         let Ok((_, stripped)) = strip_formatting_tags(self.text.as_str()) else {
             return Cow::Borrowed(&self.text);
@@ -620,6 +1266,10 @@ impl TextEvent for AssEvent {
     fn as_plaintext(&self) -> Cow<'_, String> {
         Cow::Owned(self.unformatted_text().replace("\\N", "\n"))
     }
+
+    fn speaker(&self) -> Option<String> {
+        self.name.clone().filter(|name| !name.is_empty())
+    }
 }
 
 impl TimedEvent for AssEvent {}
@@ -720,6 +1370,9 @@ impl Display for AssScriptInfo {
         if let Some(wrap_style) = &self.wrap_style {
             write!(f, "\nWrapStyle: {wrap_style}")?;
         }
+        for (key, value) in &self.extra {
+            write!(f, "\n{key}: {value}")?;
+        }
 
         Ok(())
     }
@@ -731,11 +1384,42 @@ impl Default for AssScriptInfo {
     }
 }
 
+impl Default for AssStyle {
+    /// Style named `Default`, with the attributes used for new scripts by Aegisub
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            fontname: "Arial".to_string(),
+            fontsize: 20,
+            primary_colour: "&H00FFFFFF".to_string(),
+            secondary_colour: "&H000000FF".to_string(),
+            outline_colour: "&H00000000".to_string(),
+            back_colour: "&H00000000".to_string(),
+            bold: false,
+            italic: false,
+            underline: false,
+            strike_out: false,
+            scale_x: 100,
+            scale_y: 100,
+            spacing: 0,
+            angle: 0.0,
+            border_style: 1,
+            outline: 2,
+            shadow: 2,
+            alignment: 2,
+            margin_l: 10,
+            margin_r: 10,
+            margin_v: 10,
+            encoding: 1,
+        }
+    }
+}
+
 impl Display for AssStyle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            "Style: {},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
             self.name,
             self.fontname,
             self.fontsize,
@@ -743,10 +1427,10 @@ impl Display for AssStyle {
             self.secondary_colour,
             self.outline_colour,
             self.back_colour,
-            self.bold,
-            self.italic,
-            self.underline,
-            self.strike_out,
+            format_reverse_bool(self.bold),
+            format_reverse_bool(self.italic),
+            format_reverse_bool(self.underline),
+            format_reverse_bool(self.strike_out),
             self.scale_x,
             self.scale_y,
             self.spacing,
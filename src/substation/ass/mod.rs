@@ -1,5 +1,6 @@
 pub(crate) mod convert;
 mod data;
 mod parse;
+pub mod tags;
 
-pub use data::{AssEvent, AssScriptInfo, AssStyle, AssSubtitle};
+pub use data::{AssEvent, AssScriptInfo, AssStyle, AssSubtitle, CommentPolicy};
@@ -1,7 +1,4 @@
-use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader, Read},
-};
+use std::io::{BufReader, Read};
 
 use nom::{
     branch::alt,
@@ -14,19 +11,23 @@ use nom::{
 };
 
 use crate::{
+    parsing::lines,
     substation::{
         ass::{AssEvent, AssScriptInfo, AssStyle},
         common::{
-            data::SubStationEventKind,
+            data::{field_order, values_to_standard_order, SubStationEventKind},
             parse::{
-                parse_category_header, parse_fonts, parse_graphics, parse_reverse_bool,
-                parse_timestamp, SubStationSection,
+                invalid_line_error, is_section_header, parse_category_header, parse_fonts,
+                parse_format_line, parse_graphics, parse_reverse_bool, parse_timestamp,
+                SubStationSection,
             },
         },
     },
-    AssSubtitle, Moment,
+    AssSubtitle, Error, Moment,
 };
 
+use super::data::{ASS_EVENT_FIELDS, ASS_STYLE_FIELDS};
+
 #[derive(Debug)]
 enum AssCategory<'a> {
     ScriptInfo((&'a str, &'a str)),
@@ -49,21 +50,9 @@ type EventTuple = (
     String,
 );
 
-fn parse_event_line(input: &str) -> IResult<&str, EventTuple> {
-    preceded(
-        tuple((space0, char(':'), space0)),
+fn parse_event_margins(input: &str) -> IResult<&str, (i64, i64, i64, Option<String>, String)> {
+    alt((
         tuple((
-            terminated(i64, pair(char(','), space0)),
-            terminated(parse_timestamp, pair(char(','), space0)),
-            terminated(parse_timestamp, pair(char(','), space0)),
-            terminated(
-                map(opt(take_until(",")), |s| s.map(|v: &str| v.to_string())),
-                pair(char(','), space0),
-            ),
-            terminated(
-                map(opt(take_until(",")), |s| s.map(|v: &str| v.to_string())),
-                pair(char(','), space0),
-            ),
             terminated(i64, pair(char(','), space0)),
             terminated(i64, pair(char(','), space0)),
             terminated(i64, pair(char(','), space0)),
@@ -73,6 +62,38 @@ fn parse_event_line(input: &str) -> IResult<&str, EventTuple> {
             ),
             map(rest, |s: &str| s.to_string()),
         )),
+        // Some malformed files omit the margin and effect fields entirely
+        map(rest, |s: &str| (0, 0, 0, None, s.to_string())),
+    ))
+    .parse(input)
+}
+
+fn parse_event_line(input: &str) -> IResult<&str, EventTuple> {
+    map(
+        preceded(
+            tuple((space0, char(':'), space0)),
+            pair(
+                tuple((
+                    terminated(i64, pair(char(','), space0)),
+                    terminated(parse_timestamp, pair(char(','), space0)),
+                    terminated(parse_timestamp, pair(char(','), space0)),
+                    terminated(
+                        map(opt(take_until(",")), |s| s.map(|v: &str| v.to_string())),
+                        pair(char(','), space0),
+                    ),
+                    terminated(
+                        map(opt(take_until(",")), |s| s.map(|v: &str| v.to_string())),
+                        pair(char(','), space0),
+                    ),
+                )),
+                parse_event_margins,
+            ),
+        ),
+        |((layer, start, end, style, name), (margin_l, margin_r, margin_v, effect, text))| {
+            (
+                layer, start, end, style, name, margin_l, margin_r, margin_v, effect, text,
+            )
+        },
     )
     .parse(input)
 }
@@ -101,6 +122,52 @@ fn map_ass_event<I, E>(
     )
 }
 
+/// Parse a dialogue block from a Matroska ASS track, which has the form
+/// `ReadOrder,Layer,Style,Name,MarginL,MarginR,MarginV,Effect,Text`, returning its read order
+/// and the event. Timing is stored separately from the block in Matroska, so it must be given.
+pub(crate) fn parse_mkv_block(
+    input: &str,
+    start: Moment,
+    end: Moment,
+) -> IResult<&str, (i64, AssEvent)> {
+    map(
+        pair(
+            tuple((
+                terminated(i64, pair(char(','), space0)),
+                terminated(i64, pair(char(','), space0)),
+                terminated(
+                    map(opt(take_until(",")), |s| s.map(|v: &str| v.to_string())),
+                    pair(char(','), space0),
+                ),
+                terminated(
+                    map(opt(take_until(",")), |s| s.map(|v: &str| v.to_string())),
+                    pair(char(','), space0),
+                ),
+            )),
+            parse_event_margins,
+        ),
+        move |((read_order, layer, style, name), (margin_l, margin_r, margin_v, effect, text))| {
+            (
+                read_order,
+                AssEvent {
+                    kind: SubStationEventKind::Dialogue,
+                    layer,
+                    start,
+                    end,
+                    style,
+                    name,
+                    margin_l,
+                    margin_r,
+                    margin_v,
+                    effect,
+                    text,
+                },
+            )
+        },
+    )
+    .parse(input)
+}
+
 fn parse_dialogue(input: &str) -> IResult<&str, AssEvent> {
     map_ass_event(
         preceded(tag_no_case("Dialogue"), parse_event_line),
@@ -141,6 +208,14 @@ fn parse_command(input: &str) -> IResult<&str, AssEvent> {
     .parse(input)
 }
 
+fn parse_comment(input: &str) -> IResult<&str, AssEvent> {
+    map_ass_event(
+        preceded(tag_no_case("Comment"), parse_event_line),
+        SubStationEventKind::Comment,
+    )
+    .parse(input)
+}
+
 fn parse_event(input: &str) -> IResult<&str, AssCategory> {
     map(
         alt((
@@ -149,6 +224,7 @@ fn parse_event(input: &str) -> IResult<&str, AssCategory> {
             parse_sound,
             parse_movie,
             parse_command,
+            parse_comment,
         )),
         AssCategory::Events,
     )
@@ -268,59 +344,114 @@ fn parse_nothing(input: &str) -> IResult<&str, AssCategory> {
     fail(input)
 }
 
-fn build_script_info(data: &HashMap<String, String>) -> AssScriptInfo {
-    AssScriptInfo {
-        title: data.get("Title").map(std::clone::Clone::clone),
-        original_script: data.get("Original Script").map(std::clone::Clone::clone),
-        original_translation: data
-            .get("Original Translation")
-            .map(std::clone::Clone::clone),
-        original_editing: data.get("Original Editing").map(std::clone::Clone::clone),
-        original_timing: data.get("Original Timing").map(std::clone::Clone::clone),
-        synch_point: data.get("Synch Point").map(std::clone::Clone::clone),
-        script_updated_by: data.get("Script Updated By").map(std::clone::Clone::clone),
-        update_details: data.get("Update Details").map(std::clone::Clone::clone),
-        script_type: data.get("ScriptType").map(std::clone::Clone::clone),
-        collisions: data.get("Collisions").map(std::clone::Clone::clone),
-        play_res_y: data.get("PlayResY").map(std::clone::Clone::clone),
-        play_res_x: data.get("PlayResX").map(std::clone::Clone::clone),
-        play_depth: data.get("PlayDepth").map(std::clone::Clone::clone),
-        timer: data.get("Timer").map(std::clone::Clone::clone),
-        wrap_style: data.get("WrapStyle").map(std::clone::Clone::clone),
+fn build_script_info(data: Vec<(String, String)>) -> AssScriptInfo {
+    let mut info = AssScriptInfo::builder().build();
+    for (key, value) in data {
+        match key.as_str() {
+            "Title" => info.title = Some(value),
+            "Original Script" => info.original_script = Some(value),
+            "Original Translation" => info.original_translation = Some(value),
+            "Original Editing" => info.original_editing = Some(value),
+            "Original Timing" => info.original_timing = Some(value),
+            "Synch Point" => info.synch_point = Some(value),
+            "Script Updated By" => info.script_updated_by = Some(value),
+            "Update Details" => info.update_details = Some(value),
+            "ScriptType" => info.script_type = Some(value),
+            "Collisions" => info.collisions = Some(value),
+            "PlayResY" => info.play_res_y = Some(value),
+            "PlayResX" => info.play_res_x = Some(value),
+            "PlayDepth" => info.play_depth = Some(value),
+            "Timer" => info.timer = Some(value),
+            "WrapStyle" => info.wrap_style = Some(value),
+            _ => info.extra.push((key, value)),
+        }
+    }
+
+    info
+}
+
+/// Get the parser for lines in the given section
+fn section_parser(section: Option<&SubStationSection>) -> fn(&str) -> IResult<&str, AssCategory> {
+    match section {
+        Some(SubStationSection::Events) => parse_event,
+        Some(SubStationSection::Styles) => parse_style_line,
+        Some(SubStationSection::ScriptInfo) => parse_script_info_line,
+        Some(SubStationSection::Graphics) => parse_graphic_line,
+        Some(SubStationSection::Fonts) => parse_font_line,
+        None => parse_nothing,
     }
 }
 
 pub(crate) fn parse_ass<T: Read>(reader: BufReader<T>) -> AssSubtitle {
-    let mut raw_script_info = HashMap::new();
+    // Only strict parsing can fail
+    parse_ass_lines(reader, false).unwrap_or_default()
+}
+
+/// Parse subtitles, failing on the first line which should have been parsed but could not be
+pub(crate) fn parse_ass_strict<T: Read>(reader: BufReader<T>) -> Result<AssSubtitle, Error> {
+    parse_ass_lines(reader, true)
+}
+
+fn parse_ass_lines<T: Read>(reader: BufReader<T>, strict: bool) -> Result<AssSubtitle, Error> {
+    let mut raw_script_info = Vec::new();
     let mut dialogue = Vec::new();
     let mut pictures = Vec::new();
     let mut sounds = Vec::new();
     let mut movies = Vec::new();
     let mut commands = Vec::new();
+    let mut comments = Vec::new();
+    let mut comment_positions = Vec::new();
     let mut styles = Vec::new();
     let mut raw_graphics = Vec::new();
     let mut raw_fonts = Vec::new();
+    let mut styles_format = Vec::new();
+    let mut events_format = Vec::new();
+    let mut styles_order = None;
+    let mut events_order = None;
     let mut state = None;
-    for line in reader.lines() {
-        let Ok(line) = line else {
+    let mut in_unknown_section = false;
+    for (index, line) in lines(reader).enumerate() {
+        let Ok(mut line) = line else {
             continue;
         };
-        if let Ok((_, category)) = parse_category_header(line.as_str()) {
-            state = Some(category);
+        if is_section_header(line.as_str()) {
+            let header = parse_category_header(line.as_str());
+            in_unknown_section = header.is_err();
+            if let Ok((_, category)) = header {
+                state = Some(category);
+            }
+            continue;
+        }
+        if let Ok((_, fields)) = parse_format_line(line.as_str()) {
+            match state {
+                Some(SubStationSection::Styles) => {
+                    styles_order = field_order(&fields, &ASS_STYLE_FIELDS);
+                    styles_format = fields;
+                }
+                Some(SubStationSection::Events) => {
+                    events_order = field_order(&fields, &ASS_EVENT_FIELDS);
+                    events_format = fields;
+                }
+                _ => {}
+            }
             continue;
         }
 
-        let parse_fn = match state {
-            Some(SubStationSection::Events) => parse_event,
-            Some(SubStationSection::Styles) => parse_style_line,
-            Some(SubStationSection::ScriptInfo) => parse_script_info_line,
-            Some(SubStationSection::Graphics) => parse_graphic_line,
-            Some(SubStationSection::Fonts) => parse_font_line,
-            None => parse_nothing,
+        // Parsers expect fields in the standard order
+        let order = match state {
+            Some(SubStationSection::Styles) => styles_order.as_deref(),
+            Some(SubStationSection::Events) => events_order.as_deref(),
+            _ => None,
         };
+        if let Some(order) = order {
+            line = values_to_standard_order(line.as_str(), order);
+        }
 
-        let Ok((_, block)) = parse_fn(line.as_str()) else {
-            continue;
+        let Ok((_, block)) = section_parser(state.as_ref())(line.as_str()) else {
+            match invalid_line_error(state.as_ref(), index + 1, line.as_str()) {
+                Some(err) if strict && !in_unknown_section => return Err(err),
+                _ => continue,
+            }
         };
 
         match block {
@@ -330,11 +461,15 @@ pub(crate) fn parse_ass<T: Read>(reader: BufReader<T>) -> AssSubtitle {
                 SubStationEventKind::Sound => sounds.push(event),
                 SubStationEventKind::Movie => movies.push(event),
                 SubStationEventKind::Command => commands.push(event),
+                SubStationEventKind::Comment => {
+                    comment_positions.push(dialogue.len());
+                    comments.push(event);
+                }
             },
             AssCategory::Fonts(font) => raw_fonts.push(font.to_string()),
             AssCategory::Graphics(graphic) => raw_graphics.push(graphic.to_string()),
             AssCategory::ScriptInfo((key, value)) => {
-                raw_script_info.insert(key.to_string(), value.to_string());
+                raw_script_info.push((key.to_string(), value.to_string()));
             }
             AssCategory::Styles(style) => styles.push(style),
         }
@@ -350,15 +485,19 @@ pub(crate) fn parse_ass<T: Read>(reader: BufReader<T>) -> AssSubtitle {
         Err(_) => Vec::new(),
     };
 
-    AssSubtitle::builder()
-        .script_info(build_script_info(&raw_script_info))
+    Ok(AssSubtitle::builder()
+        .script_info(build_script_info(raw_script_info))
         .dialogue(dialogue)
         .pictures(pictures)
         .sounds(sounds)
         .movies(movies)
         .commands(commands)
+        .comments(comments)
         .styles(styles)
         .fonts(fonts)
         .graphics(graphics)
+        .styles_format(styles_format)
+        .events_format(events_format)
         .build()
+        .with_comment_positions(comment_positions))
 }
@@ -0,0 +1,347 @@
+//! Parsing of the override tags used for inline formatting in Advanced SubStation Alpha (.ass) text,
+//! e.g. `{\b1\pos(320,50)}`.
+
+use crate::TimeDelta;
+
+/// Kind of karaoke effect
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssKaraokeKind {
+    /// Syllable is highlighted instantly (`\k`)
+    Standard,
+    /// Syllable is filled in from left to right (`\kf` or `\K`)
+    Fill,
+    /// Outline of the syllable is highlighted instantly (`\ko`)
+    Outline,
+}
+
+/// Override tag, as found in an override block
+#[derive(Clone, Debug, PartialEq)]
+pub enum AssTag {
+    /// Bold (`\b`), either 0 or 1, or a font weight such as 700
+    Bold(u32),
+    /// Italic (`\i`)
+    Italic(bool),
+    /// Underline (`\u`)
+    Underline(bool),
+    /// Strikeout (`\s`)
+    StrikeOut(bool),
+    /// Font name (`\fn`)
+    FontName(String),
+    /// Font size (`\fs`)
+    FontSize(f64),
+    /// Colour (`\c` or `\1c` to `\4c`), as written, e.g. `&H0000FF&`
+    Colour {
+        /// Which colour is set, from 1 (primary) to 4 (shadow)
+        index: u8,
+        /// Colour value in BGR hex
+        colour: String,
+    },
+    /// Alpha (`\alpha` or `\1a` to `\4a`), as written, e.g. `&H80&`
+    Alpha {
+        /// Which alpha is set, from 1 (primary) to 4 (shadow), or 0 for all of them
+        index: u8,
+        /// Alpha value in hex
+        alpha: String,
+    },
+    /// Position (`\pos(x,y)`)
+    Position {
+        /// Horizontal position
+        x: f64,
+        /// Vertical position
+        y: f64,
+    },
+    /// Movement (`\move(x1,y1,x2,y2)` or `\move(x1,y1,x2,y2,t1,t2)`)
+    Move {
+        /// Starting position
+        from: (f64, f64),
+        /// Final position
+        to: (f64, f64),
+        /// Start and end time of the movement in milliseconds, relative to the start of the event
+        times: Option<(i64, i64)>,
+    },
+    /// Fade in and out (`\fad(in,out)`), in milliseconds
+    Fade {
+        /// Duration of the fade in
+        fade_in: i64,
+        /// Duration of the fade out
+        fade_out: i64,
+    },
+    /// Karaoke (`\k`, `\kf`, `\K` or `\ko`), with the duration of the syllable which follows it
+    Karaoke {
+        /// Kind of karaoke effect
+        kind: AssKaraokeKind,
+        /// Duration of the syllable
+        duration: TimeDelta,
+    },
+    /// Animated transform (`\t`) of the given tags
+    Transform {
+        /// Start and end time of the transform in milliseconds, relative to the start of the event
+        times: Option<(i64, i64)>,
+        /// Acceleration of the transform
+        accel: Option<f64>,
+        /// Tags to transform to
+        tags: Vec<AssTag>,
+    },
+    /// Reset (`\r`) to the style of the event, or to the given style
+    Reset(Option<String>),
+    /// Any other tag, as written without the leading backslash
+    Other(String),
+}
+
+/// Run of text in an event, along with the tags of the override blocks directly before it
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssTagSpan {
+    /// Tags which apply from the start of this span
+    pub tags: Vec<AssTag>,
+    /// Text of the span, which may be empty if the event ends with an override block
+    pub text: String,
+}
+
+/// Split the content of an override block into individual tags, each without its leading backslash
+fn split_tags(input: &str) -> Vec<&str> {
+    let mut tags = Vec::new();
+    let mut start = None;
+    let mut depth = 0;
+
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '\\' if depth <= 0 => {
+                if let Some(start) = start {
+                    tags.push(&input[start..i]);
+                }
+                start = Some(i + 1);
+                depth = 0;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = start {
+        tags.push(&input[start..]);
+    }
+
+    tags.into_iter()
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Arguments of a tag of the form `name(a,b,...)`
+fn arguments<'a>(tag: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let arguments = tag.strip_prefix(name)?.trim_start().strip_prefix('(')?;
+    let arguments = arguments.strip_suffix(')').unwrap_or(arguments);
+
+    Some(arguments.split(',').map(str::trim).collect())
+}
+
+fn numbers<T: std::str::FromStr>(arguments: &[&str]) -> Option<Vec<T>> {
+    arguments
+        .iter()
+        .map(|argument| argument.parse().ok())
+        .collect()
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => None,
+    }
+}
+
+/// Parse the indexed colour and alpha tags (`\1c` to `\4c`, `\1a` to `\4a`)
+fn parse_indexed(tag: &str) -> Option<AssTag> {
+    let mut chars = tag.chars();
+    let index = chars
+        .next()?
+        .to_digit(10)
+        .filter(|index| (1..=4).contains(index))?;
+    let index = u8::try_from(index).ok()?;
+    let value = chars.as_str();
+
+    if let Some(colour) = value.strip_prefix('c') {
+        Some(AssTag::Colour {
+            index,
+            colour: colour.to_string(),
+        })
+    } else {
+        value.strip_prefix('a').map(|alpha| AssTag::Alpha {
+            index,
+            alpha: alpha.to_string(),
+        })
+    }
+}
+
+fn parse_transform(tag: &str) -> Option<AssTag> {
+    let inner = tag.strip_prefix("t(")?;
+    let inner = inner.strip_suffix(')').unwrap_or(inner);
+    let tags_start = inner.find('\\')?;
+    let parameters: Vec<&str> = inner[..tags_start]
+        .split(',')
+        .map(str::trim)
+        .filter(|parameter| !parameter.is_empty())
+        .collect();
+
+    let (times, accel) = match parameters.as_slice() {
+        [] => (None, None),
+        [accel] => (None, Some(accel.parse().ok()?)),
+        [t1, t2] => (Some((t1.parse().ok()?, t2.parse().ok()?)), None),
+        [t1, t2, accel] => (
+            Some((t1.parse().ok()?, t2.parse().ok()?)),
+            Some(accel.parse().ok()?),
+        ),
+        _ => return None,
+    };
+
+    Some(AssTag::Transform {
+        times,
+        accel,
+        tags: parse_override_block(&inner[tags_start..]),
+    })
+}
+
+fn parse_karaoke(tag: &str) -> Option<AssTag> {
+    let (kind, duration) = if let Some(duration) = tag.strip_prefix("kf") {
+        (AssKaraokeKind::Fill, duration)
+    } else if let Some(duration) = tag.strip_prefix("ko") {
+        (AssKaraokeKind::Outline, duration)
+    } else if let Some(duration) = tag.strip_prefix('K') {
+        (AssKaraokeKind::Fill, duration)
+    } else {
+        (AssKaraokeKind::Standard, tag.strip_prefix('k')?)
+    };
+    let centiseconds: i64 = duration.parse().ok()?;
+
+    Some(AssTag::Karaoke {
+        kind,
+        duration: TimeDelta::from(centiseconds * 10),
+    })
+}
+
+fn parse_tag(tag: &str) -> Option<AssTag> {
+    if let Some(arguments) = arguments(tag, "pos") {
+        let [x, y] = numbers::<f64>(&arguments)?[..] else {
+            return None;
+        };
+        return Some(AssTag::Position { x, y });
+    }
+    if let Some(arguments) = arguments(tag, "move") {
+        let values = numbers::<f64>(&arguments)?;
+        #[allow(clippy::cast_possible_truncation)]
+        return match values[..] {
+            [x1, y1, x2, y2] => Some(AssTag::Move {
+                from: (x1, y1),
+                to: (x2, y2),
+                times: None,
+            }),
+            [x1, y1, x2, y2, t1, t2] => Some(AssTag::Move {
+                from: (x1, y1),
+                to: (x2, y2),
+                times: Some((t1.round() as i64, t2.round() as i64)),
+            }),
+            _ => None,
+        };
+    }
+    if let Some(arguments) = arguments(tag, "fad") {
+        let [fade_in, fade_out] = numbers::<i64>(&arguments)?[..] else {
+            return None;
+        };
+        return Some(AssTag::Fade { fade_in, fade_out });
+    }
+    if tag.starts_with("t(") {
+        return parse_transform(tag);
+    }
+    if tag.starts_with(|c: char| c.is_ascii_digit()) {
+        return parse_indexed(tag);
+    }
+    if let Some(alpha) = tag.strip_prefix("alpha") {
+        return Some(AssTag::Alpha {
+            index: 0,
+            alpha: alpha.to_string(),
+        });
+    }
+    if let Some(name) = tag.strip_prefix("fn") {
+        return Some(AssTag::FontName(name.trim().to_string()));
+    }
+    if let Some(size) = tag.strip_prefix("fs") {
+        return size.parse().ok().map(AssTag::FontSize);
+    }
+    if let Some(colour) = tag
+        .strip_prefix('c')
+        .filter(|colour| colour.starts_with('&'))
+    {
+        return Some(AssTag::Colour {
+            index: 1,
+            colour: colour.to_string(),
+        });
+    }
+    if let Some(style) = tag.strip_prefix('r') {
+        let style = style.trim();
+        return Some(AssTag::Reset(
+            (!style.is_empty()).then(|| style.to_string()),
+        ));
+    }
+    if tag.starts_with(['k', 'K']) {
+        return parse_karaoke(tag);
+    }
+
+    let (name, value) = tag.split_at(tag.find(|c: char| !c.is_ascii_alphabetic())?);
+    match name {
+        "b" => value.parse().ok().map(AssTag::Bold),
+        "i" => parse_flag(value).map(AssTag::Italic),
+        "u" => parse_flag(value).map(AssTag::Underline),
+        "s" => parse_flag(value).map(AssTag::StrikeOut),
+        _ => None,
+    }
+}
+
+/// Parse the tags of an override block, such as `{\b1\pos(320,50)}`.
+///
+/// The surrounding braces are optional. Tags which are not recognised, or whose values cannot be
+/// parsed, are returned as [`AssTag::Other`]. Any text in the block which is not part of a tag is ignored.
+#[must_use]
+pub fn parse_override_block(input: &str) -> Vec<AssTag> {
+    let input = input.strip_prefix('{').unwrap_or(input);
+    let input = input.strip_suffix('}').unwrap_or(input);
+
+    split_tags(input)
+        .into_iter()
+        .map(|tag| parse_tag(tag).unwrap_or_else(|| AssTag::Other(tag.to_string())))
+        .collect()
+}
+
+/// Split text into runs of text, each with the tags of the override blocks directly before it
+pub(crate) fn tag_spans(input: &str) -> Vec<AssTagSpan> {
+    let mut spans = Vec::new();
+    let mut tags = Vec::new();
+    let mut remaining = input;
+
+    while !remaining.is_empty() {
+        if let Some(block) = remaining.strip_prefix('{') {
+            if let Some((content, rest)) = block.split_once('}') {
+                tags.extend(parse_override_block(content));
+                remaining = rest;
+                continue;
+            }
+        }
+
+        let first = remaining.chars().next().map_or(0, char::len_utf8);
+        let end = remaining[first..]
+            .find('{')
+            .map_or(remaining.len(), |i| i + first);
+        spans.push(AssTagSpan {
+            tags: std::mem::take(&mut tags),
+            text: remaining[..end].to_string(),
+        });
+        remaining = &remaining[end..];
+    }
+    if !tags.is_empty() {
+        spans.push(AssTagSpan {
+            tags,
+            text: String::new(),
+        });
+    }
+
+    spans
+}
@@ -63,3 +63,67 @@ pub(crate) fn convert_font_color_tag(input: &str) -> IResult<&str, String> {
     )
     .parse(input)
 }
+
+const CARRIED_TAGS: [&str; 3] = ["b", "i", "u"];
+
+/// Close any formatting tags left open at a line break, and reopen them on the following line.
+///
+/// SubStation override tags apply until they are turned off, even across `\N`, while each line
+/// of the converted HTML-style formatting should be balanced on its own.
+pub(crate) fn reopen_tags_across_lines(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut open: Vec<&str> = Vec::new();
+    let mut pending = false;
+    let mut remaining = input;
+
+    while let Some(c) = remaining.chars().next() {
+        if let Some(name) = CARRIED_TAGS
+            .iter()
+            .find(|name| remaining.starts_with(format!("<{name}>").as_str()))
+        {
+            if pending {
+                for tag in &open {
+                    write!(result, "<{tag}>").unwrap();
+                }
+                pending = false;
+            }
+            write!(result, "<{name}>").unwrap();
+            open.push(name);
+            remaining = &remaining[name.len() + 2..];
+            continue;
+        }
+
+        if let Some(name) = CARRIED_TAGS
+            .iter()
+            .find(|name| remaining.starts_with(format!("</{name}>").as_str()))
+        {
+            if let Some(position) = open.iter().rposition(|tag| tag == name) {
+                open.remove(position);
+            }
+            if !pending {
+                write!(result, "</{name}>").unwrap();
+            }
+            remaining = &remaining[name.len() + 3..];
+            continue;
+        }
+
+        if c == '\n' {
+            if !pending && !open.is_empty() {
+                for tag in open.iter().rev() {
+                    write!(result, "</{tag}>").unwrap();
+                }
+                pending = true;
+            }
+        } else if pending {
+            for tag in &open {
+                write!(result, "<{tag}>").unwrap();
+            }
+            pending = false;
+        }
+
+        result.push(c);
+        remaining = &remaining[c.len_utf8()..];
+    }
+
+    result
+}
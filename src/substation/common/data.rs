@@ -1,7 +1,10 @@
 use std::fmt::Display;
 
 /// Types of events in SubStation files
-#[derive(Clone, Copy, Debug)]
+///
+/// The keywords identifying each type of event (e.g. `Dialogue:`) are parsed case-insensitively,
+/// but are always written in title case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SubStationEventKind {
     /// Dialogue event. Used to show text on screen to represent dialogue or other textual content.
     Dialogue,
@@ -13,10 +16,12 @@ pub enum SubStationEventKind {
     Movie,
     /// Command event. Used to execute arbitrary commands during playback
     Command,
+    /// Comment event. Not shown during playback, but can be used to store notes, e.g. for translators.
+    Comment,
 }
 
 /// Embedded font data for SubStation files
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SubStationFont {
     /// Name of font
     pub fontname: String,
@@ -25,7 +30,7 @@ pub struct SubStationFont {
 }
 
 /// Embedded graphics data for SubStation files
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SubStationGraphic {
     /// Name of file
     pub filename: String,
@@ -41,6 +46,7 @@ impl Display for SubStationEventKind {
             SubStationEventKind::Sound => "Sound",
             SubStationEventKind::Movie => "Movie",
             SubStationEventKind::Command => "Command",
+            SubStationEventKind::Comment => "Comment",
         };
 
         write!(f, "{representation}")
@@ -58,3 +64,110 @@ impl Display for SubStationGraphic {
         writeln!(f, "filename: {}\n{}", self.filename, self.data)
     }
 }
+
+/// Format a boolean the way SubStation files represent them, as `-1` for true and `0` for false
+pub(crate) fn format_reverse_bool(value: bool) -> i64 {
+    if value {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Whether two style names refer to the same style.
+///
+/// Names are compared case-insensitively, as done by Aegisub. Some tools prefix style names
+/// with `*` (e.g. `*Default`), which is ignored for matching.
+pub(crate) fn style_names_match(a: &str, b: &str) -> bool {
+    let (a, b) = (a.trim_start_matches('*'), b.trim_start_matches('*'));
+
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .eq(b.chars().flat_map(char::to_lowercase))
+}
+
+/// Map each field of a `Format:` line to the position of the same field in `standard`.
+///
+/// Returns `None` unless the fields are a reordering of the standard fields in which `Text`, if present,
+/// is still last, since it is the only field which may contain commas. In that case the standard order is used instead.
+/// Field names are matched case-insensitively, and `Actor` is accepted in place of `Name`.
+pub(crate) fn field_order(fields: &[String], standard: &[&str]) -> Option<Vec<usize>> {
+    let normalize = |field: &str| {
+        let field = field.trim().to_ascii_lowercase();
+        if field == "actor" {
+            "name".to_string()
+        } else {
+            field
+        }
+    };
+    let order: Vec<usize> = fields
+        .iter()
+        .map(|field| {
+            standard
+                .iter()
+                .position(|name| normalize(name) == normalize(field))
+        })
+        .collect::<Option<_>>()?;
+
+    if let Some(text) = standard.iter().position(|name| *name == "Text") {
+        if order.last() != Some(&text) {
+            return None;
+        }
+    }
+
+    let mut distinct = order.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+
+    (distinct.len() == standard.len() && order.len() == standard.len()).then_some(order)
+}
+
+/// Write a `Format:` line, using the given fields if they are in use and the standard fields otherwise
+pub(crate) fn write_format_line(
+    f: &mut std::fmt::Formatter<'_>,
+    fields: &[String],
+    standard: &[&str],
+    use_fields: bool,
+) -> std::fmt::Result {
+    if use_fields {
+        writeln!(f, "Format: {}", fields.join(", "))
+    } else {
+        writeln!(f, "Format: {}", standard.join(", "))
+    }
+}
+
+/// Split a line such as `Dialogue: 0,0:00:01.00,...` into its key and its comma separated values,
+/// where the last value may contain commas
+fn split_values(line: &str, count: usize) -> Option<(&str, Vec<&str>)> {
+    let (key, values) = line.split_once(':')?;
+    let values: Vec<&str> = values.trim_start().splitn(count, ',').collect();
+
+    (values.len() == count).then_some((key, values))
+}
+
+/// Reorder the values of a line from the order given by `order` (see [`field_order`]) to the standard order
+pub(crate) fn values_to_standard_order(line: &str, order: &[usize]) -> String {
+    let Some((key, values)) = split_values(line, order.len()) else {
+        return line.to_string();
+    };
+
+    let mut reordered = vec![""; order.len()];
+    for (value, &index) in values.into_iter().zip(order) {
+        reordered[index] = value;
+    }
+
+    format!("{key}: {}", reordered.join(","))
+}
+
+/// Reorder the values of a line from the standard order to the order given by `order` (see [`field_order`])
+pub(crate) fn values_from_standard_order(line: &str, order: Option<&[usize]>) -> String {
+    let Some((key, values, order)) =
+        order.and_then(|order| split_values(line, order.len()).map(|(k, v)| (k, v, order)))
+    else {
+        return line.to_string();
+    };
+
+    let reordered: Vec<&str> = order.iter().map(|&index| values[index]).collect();
+
+    format!("{key}: {}", reordered.join(","))
+}
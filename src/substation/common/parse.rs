@@ -2,15 +2,16 @@ use nom::{
     branch::alt,
     bytes::complete::{tag, tag_no_case, take_until},
     character::complete::{anychar, char, i64, multispace0, space0},
-    combinator::{map, value},
+    combinator::{map, rest, value},
     multi::{many0, many_till},
-    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     IResult, Parser,
 };
 
 use crate::{
+    parsing::fraction_ms,
     substation::{SubStationFont, SubStationGraphic},
-    Format, Moment,
+    Error, Format, Moment,
 };
 
 #[derive(Clone, Debug)]
@@ -47,6 +48,50 @@ pub(crate) fn parse_format(input: &str) -> IResult<&str, Format> {
     map(many_till(anychar, parse_script_type), |(_, format)| format).parse(input)
 }
 
+/// Detect the format of a subtitle without a `[Script Info]` section from its first section header.
+///
+/// For `[Events]`, the format line is used, since only .ssa events have a `Marked` field.
+pub(crate) fn parse_format_from_section(input: &str) -> IResult<&str, Format> {
+    preceded(
+        multispace0,
+        alt((
+            value(Format::Ass, tag_no_case("[V4+ Styles]")),
+            value(Format::Ssa, tag_no_case("[V4 Styles]")),
+            map(
+                preceded(
+                    pair(tag_no_case("[Events]"), multispace0),
+                    alt((take_until("\n"), rest)),
+                ),
+                |format_line: &str| {
+                    if format_line.to_ascii_lowercase().contains("marked") {
+                        Format::Ssa
+                    } else {
+                        Format::Ass
+                    }
+                },
+            ),
+        )),
+    )
+    .parse(input)
+}
+
+/// Parse the field names of a `Format:` line in the `[Events]` or styles section
+pub(crate) fn parse_format_line(input: &str) -> IResult<&str, Vec<String>> {
+    map(
+        preceded(
+            tuple((space0, tag_no_case("Format"), space0, char(':'))),
+            rest,
+        ),
+        |fields: &str| {
+            fields
+                .split(',')
+                .map(|field| field.trim().to_string())
+                .collect()
+        },
+    )
+    .parse(input)
+}
+
 fn parse_script_info_header(input: &str) -> IResult<&str, SubStationSection> {
     value(SubStationSection::ScriptInfo, tag_no_case("Script Info")).parse(input)
 }
@@ -90,6 +135,37 @@ pub(crate) fn parse_category_header(input: &str) -> IResult<&str, SubStationSect
     .parse(input)
 }
 
+/// Whether a line looks like a section header, including headers of sections which are not supported
+pub(crate) fn is_section_header(line: &str) -> bool {
+    line.trim_start().starts_with('[')
+}
+
+/// Create an error describing why a line which could not be parsed is invalid in the given
+/// section, or `None` if the line is allowed to be skipped (e.g. blank lines and comments).
+pub(crate) fn invalid_line_error(
+    section: Option<&SubStationSection>,
+    line_number: usize,
+    line: &str,
+) -> Option<Error> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with(';') {
+        return None;
+    }
+
+    let message = match section {
+        None => Some("line is outside of any section".to_string()),
+        Some(SubStationSection::Events) => Some("unrecognized event line".to_string()),
+        Some(SubStationSection::Styles) => Some("unrecognized style line".to_string()),
+        Some(SubStationSection::ScriptInfo) => Some("unrecognized script info line".to_string()),
+        Some(SubStationSection::Fonts | SubStationSection::Graphics) => None,
+    };
+
+    message.map(|message| Error::ParseError {
+        line: line_number,
+        message,
+    })
+}
+
 pub(crate) fn parse_reverse_bool(input: &str) -> IResult<&str, bool> {
     alt((value(true, tag("-1")), value(false, char('0')))).parse(input)
 }
@@ -102,9 +178,13 @@ fn parse_fontname(input: &str) -> IResult<&str, &str> {
     .parse(input)
 }
 
+fn parse_embedded_data(input: &str) -> IResult<&str, &str> {
+    map(alt((take_until("\n\n"), rest)), str::trim_end).parse(input)
+}
+
 fn parse_font(input: &str) -> IResult<&str, SubStationFont> {
     map(
-        separated_pair(parse_fontname, multispace0, take_until("\n\n")),
+        separated_pair(parse_fontname, multispace0, parse_embedded_data),
         |(fontname, data)| SubStationFont {
             fontname: fontname.to_string(),
             data: data.to_string(),
@@ -127,7 +207,7 @@ fn parse_filename(input: &str) -> IResult<&str, &str> {
 
 fn parse_graphic(input: &str) -> IResult<&str, SubStationGraphic> {
     map(
-        separated_pair(parse_filename, multispace0, take_until("\n\n")),
+        separated_pair(parse_filename, multispace0, parse_embedded_data),
         |(filename, data)| SubStationGraphic {
             filename: filename.to_string(),
             data: data.to_string(),
@@ -148,11 +228,11 @@ pub(crate) fn parse_timestamp(input: &str) -> IResult<&str, Moment> {
                 terminated(i64, char(':')),
                 i64,
                 delimited(char(':'), i64, char('.')),
-                i64,
+                fraction_ms,
             )),
             space0,
         ),
-        |(h, m, s, cs)| Moment::from_timestamp(h, m, s, cs * 10),
+        |(h, m, s, ms)| Moment::from_timestamp(h, m, s, ms),
     )
     .parse(input)
 }
@@ -1,25 +1,63 @@
-use std::{borrow::Cow, fmt::Display, fs::File, io::BufReader, path::Path, str::FromStr};
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    io::{BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
 
 use buildstructor::Builder;
 use encoding_rs::Encoding;
-use encoding_rs_io::DecodeReaderBytesBuilder;
 
 use crate::{
-    encoding::detect_file_encoding,
+    encoding::{parse_from_reader, read_file_with_encoding},
     errors::Error,
+    microdvd::convert::mdvd_to_substation_formatting,
     plain::PlainSubtitle,
+    sdh::remove_sdh_annotations,
     subrip::convert::srt_to_ssa_formatting,
-    substation::common::data::{SubStationEventKind, SubStationFont, SubStationGraphic},
-    traits::TimedSubtitle,
+    substation::common::data::{
+        field_order, format_reverse_bool, style_names_match, values_from_standard_order,
+        write_format_line, SubStationEventKind, SubStationFont, SubStationGraphic,
+    },
+    traits::{mark_rtl_lines, RetainEvents, TimedSubtitle},
     webvtt::convert::vtt_to_ass_formatting,
-    AssSubtitle, Moment, SubRipSubtitle, Subtitle, TextEvent, TextEventInterface, TextSubtitle,
-    TimedEvent, TimedEventInterface, TimedMicroDvdSubtitle, TimedSubtitleFile, WebVttSubtitle,
+    AssSubtitle, IdentifiableEvent, Moment, SubRipSubtitle, Subtitle, TextEvent,
+    TextEventInterface, TextSubtitle, TimeDelta, TimedEvent, TimedEventInterface,
+    TimedMicroDvdSubtitle, TimedSubtitleFile, WebVttSubtitle,
 };
 
 use super::{convert::strip_formatting_tags, parse::parse_ssa};
 
+/// Fields of a style line, in the standard order
+pub(crate) const SSA_STYLE_FIELDS: [&str; 18] = [
+    "Name",
+    "Fontname",
+    "Fontsize",
+    "PrimaryColour",
+    "SecondaryColour",
+    "TertiaryColour",
+    "BackColour",
+    "Bold",
+    "Italic",
+    "BorderStyle",
+    "Outline",
+    "Shadow",
+    "Alignment",
+    "MarginL",
+    "MarginR",
+    "MarginV",
+    "AlphaLevel",
+    "Encoding",
+];
+
+/// Fields of an event line, in the standard order
+pub(crate) const SSA_EVENT_FIELDS: [&str; 10] = [
+    "Marked", "Start", "End", "Style", "Name", "MarginL", "MarginR", "MarginV", "Effect", "Text",
+];
+
 /// SubStation Alpha v4 (.ssa) subtitle
-#[derive(Debug, Builder)]
+#[derive(Clone, Debug, Builder)]
 pub struct SsaSubtitle {
     /// Script info
     script_info: SsaScriptInfo,
@@ -40,10 +78,14 @@ pub struct SsaSubtitle {
     fonts: Vec<SubStationFont>,
     /// Embedded graphics data
     graphics: Vec<SubStationGraphic>,
+    /// Fields of the styles `Format:` line, in the order they were given in the parsed file
+    styles_format: Vec<String>,
+    /// Fields of the `[Events]` `Format:` line, in the order they were given in the parsed file
+    events_format: Vec<String>,
 }
 
 /// Event in SubStation Alpha (.ssa) file
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SsaEvent {
     /// Kind of event, for example dialogue
     pub kind: SubStationEventKind,
@@ -72,7 +114,7 @@ pub struct SsaEvent {
 
 /// Information for the `[ScriptInfo]` section of an SubStation Alpha (.ssa) subtitle.
 /// It should always be the first thing shown in an .ssa format subtitle.
-#[derive(Debug, Builder)]
+#[derive(Clone, Debug, Builder)]
 pub struct SsaScriptInfo {
     /// Title/description for the subtitle
     pub title: Option<String>,
@@ -103,10 +145,13 @@ pub struct SsaScriptInfo {
     pub play_depth: Option<String>,
     /// Time scale, with 100 representing the original speed
     pub timer: Option<String>,
+    /// Other properties which are not recognised, as pairs of keys and values in the order they were given,
+    /// e.g. `ScaledBorderAndShadow: yes`
+    pub extra: Vec<(String, String)>,
 }
 
 /// Style in a .ssa file
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SsaStyle {
     /// Name of style
     pub name: String,
@@ -147,6 +192,24 @@ pub struct SsaStyle {
 }
 
 impl SsaSubtitle {
+    /// Get fields of the `Format:` line of the `[Events]` section, as given in the parsed file.
+    ///
+    /// Empty if the subtitle was not parsed from a file with such a line.
+    /// When written, events use this field order if it is a reordering of the standard fields,
+    /// otherwise the standard order is used.
+    #[must_use]
+    pub fn events_format_fields(&self) -> &[String] {
+        self.events_format.as_slice()
+    }
+
+    /// Get fields of the `Format:` line of the styles section, as given in the parsed file.
+    ///
+    /// See [`SsaSubtitle::events_format_fields`] for how these are used when writing.
+    #[must_use]
+    pub fn styles_format_fields(&self) -> &[String] {
+        self.styles_format.as_slice()
+    }
+
     /// Get list of picture events as a slice
     #[must_use]
     pub fn pictures(&self) -> &[SsaEvent] {
@@ -235,6 +298,41 @@ impl SsaSubtitle {
         self.commands.get_mut(index)
     }
 
+    /// Iterate over events of every kind, in the order they are written:
+    /// dialogue, pictures, sounds, movies, and then commands
+    pub fn all_events(&self) -> impl Iterator<Item = &SsaEvent> {
+        self.dialogue
+            .iter()
+            .chain(&self.pictures)
+            .chain(&self.sounds)
+            .chain(&self.movies)
+            .chain(&self.commands)
+    }
+
+    /// Iterate mutably over events of every kind, in the order they are written:
+    /// dialogue, pictures, sounds, movies, and then commands
+    pub fn all_events_mut(&mut self) -> impl Iterator<Item = &mut SsaEvent> {
+        self.dialogue
+            .iter_mut()
+            .chain(&mut self.pictures)
+            .chain(&mut self.sounds)
+            .chain(&mut self.movies)
+            .chain(&mut self.commands)
+    }
+
+    /// Retain only the events of every kind for which the given predicate returns `true`
+    fn retain_all_events(&mut self, mut f: impl FnMut(&SsaEvent) -> bool) {
+        for events in [
+            &mut self.dialogue,
+            &mut self.pictures,
+            &mut self.sounds,
+            &mut self.movies,
+            &mut self.commands,
+        ] {
+            events.retain(&mut f);
+        }
+    }
+
     /// Get script info struct
     #[must_use]
     pub fn script_info(&self) -> &SsaScriptInfo {
@@ -257,6 +355,60 @@ impl SsaSubtitle {
         self.styles.as_mut_slice()
     }
 
+    /// Get style with the given name.
+    ///
+    /// Names are matched case-insensitively, and a leading `*` in either name is ignored,
+    /// so `*default` will match a style named `Default`.
+    #[must_use]
+    pub fn style_by_name(&self, name: &str) -> Option<&SsaStyle> {
+        self.styles
+            .iter()
+            .find(|style| style_names_match(style.name.as_str(), name))
+    }
+
+    /// Get mutable style with the given name, matched in the same way as [`SsaSubtitle::style_by_name`]
+    pub fn style_by_name_mut(&mut self, name: &str) -> Option<&mut SsaStyle> {
+        self.styles
+            .iter_mut()
+            .find(|style| style_names_match(style.name.as_str(), name))
+    }
+
+    /// Get the style used by the given event.
+    ///
+    /// If the event has no style, or its style does not exist, the style named `Default` is used instead.
+    #[must_use]
+    pub fn event_style(&self, event: &SsaEvent) -> Option<&SsaStyle> {
+        event
+            .style
+            .as_deref()
+            .and_then(|name| self.style_by_name(name))
+            .or_else(|| self.style_by_name("Default"))
+    }
+
+    /// Add a style to the end of the list of styles
+    pub fn add_style(&mut self, style: SsaStyle) {
+        self.styles.push(style);
+    }
+
+    /// Remove all styles with the given name.
+    ///
+    /// Names are matched in the same way as [`SsaSubtitle::style_by_name`]. Events which use the removed
+    /// style will have their style cleared.
+    pub fn remove_style(&mut self, name: &str) {
+        self.styles
+            .retain(|style| !style_names_match(style.name.as_str(), name));
+
+        for event in self.all_events_mut() {
+            if event
+                .style
+                .as_deref()
+                .is_some_and(|style| style_names_match(style, name))
+            {
+                event.style = None;
+            }
+        }
+    }
+
     /// Get list of fonts as a slice
     #[must_use]
     pub fn fonts(&self) -> &[SubStationFont] {
@@ -278,23 +430,44 @@ impl SsaSubtitle {
     pub fn graphics_mut(&mut self) -> &mut [SubStationGraphic] {
         self.graphics.as_mut_slice()
     }
+}
 
-    fn open_file_with_encoding(
-        path: &Path,
-        encoding: Option<&'static Encoding>,
-    ) -> Result<Self, Error> {
-        let file = File::open(path)?;
-        let transcoded = DecodeReaderBytesBuilder::new()
-            .encoding(encoding)
-            .build(file);
-        let reader = BufReader::new(transcoded);
+impl TimedSubtitle for SsaSubtitle {
+    /// Shift all events by the given amount of time, in milliseconds.
+    ///
+    /// Unlike [`Subtitle::events`], this includes picture, sound, movie and command events in addition to dialogue.
+    fn shift(&mut self, delta: TimeDelta) {
+        for event in self.all_events_mut() {
+            event.shift(delta);
+        }
+    }
 
-        Ok(parse_ssa(reader))
+    /// Split the subtitle at the given moment, keeping events before `at` and returning a new
+    /// subtitle containing the events after `at`.
+    ///
+    /// Unlike [`Subtitle::events`], this includes picture, sound, movie and command events in addition to dialogue.
+    /// Events which start before and end after `at` are split into two, as with the default implementation.
+    fn split_at(&mut self, at: Moment) -> Self {
+        let mut tail = self.clone();
+
+        self.retain_all_events(|event| event.start < at);
+        for event in self.all_events_mut() {
+            if event.end > at {
+                event.end = at;
+            }
+        }
+
+        tail.retain_all_events(|event| event.start >= at || event.end > at);
+        for event in tail.all_events_mut() {
+            if event.start < at {
+                event.start = at;
+            }
+        }
+
+        tail
     }
 }
 
-impl TimedSubtitle for SsaSubtitle {}
-
 impl Subtitle for SsaSubtitle {
     type Event = SsaEvent;
 
@@ -302,15 +475,14 @@ impl Subtitle for SsaSubtitle {
         path: impl AsRef<Path>,
         encoding: Option<&'static Encoding>,
     ) -> Result<Self, Error> {
-        let mut enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), Some(40)).ok());
-        let mut result = Self::open_file_with_encoding(path.as_ref(), enc);
+        let text = read_file_with_encoding(path.as_ref(), encoding, Some(40))?;
+        let reader = BufReader::new(text.as_bytes());
 
-        if encoding.is_none() && result.is_err() {
-            enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), None).ok());
-            result = Self::open_file_with_encoding(path.as_ref(), enc);
-        }
+        Ok(parse_ssa(reader))
+    }
 
-        result
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        parse_from_reader(reader, |reader| parse_ssa(BufReader::new(reader)))
     }
 
     fn events(&self) -> &[SsaEvent] {
@@ -322,6 +494,12 @@ impl Subtitle for SsaSubtitle {
     }
 }
 
+impl RetainEvents for SsaSubtitle {
+    fn retain_events(&mut self, f: impl FnMut(&Self::Event) -> bool) {
+        self.dialogue.retain(f);
+    }
+}
+
 impl TextSubtitle for SsaSubtitle {
     /// Strip formatting tags from lines in addition to deleting styles
     fn strip_formatting(&mut self) {
@@ -333,49 +511,56 @@ impl TextSubtitle for SsaSubtitle {
 }
 
 impl Display for SsaSubtitle {
+    /// Write subtitle in .ssa format.
+    ///
+    /// Lines are normalized rather than reproduced exactly as parsed. In particular, the keyword of
+    /// each style and event line is always written in title case followed by `: ` (e.g. `Dialogue: `),
+    /// even if the parsed file used a different case (e.g. `dialogue:`), which is accepted when parsing.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{}", self.script_info)?;
         writeln!(f)?;
         if !self.styles.is_empty() {
             writeln!(f, "[V4 Styles]")?;
-            writeln!(f, "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, TertiaryColour, BackColour, Bold, Italic, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, AlphaLevel, Encoding")?;
+            let order = field_order(&self.styles_format, &SSA_STYLE_FIELDS);
+            write_format_line(f, &self.styles_format, &SSA_STYLE_FIELDS, order.is_some())?;
             for style in &self.styles {
-                writeln!(f, "{style}")?;
+                writeln!(
+                    f,
+                    "{}",
+                    values_from_standard_order(&style.to_string(), order.as_deref())
+                )?;
             }
             writeln!(f)?;
         }
         if !self.fonts.is_empty() {
-            writeln!(f)?;
+            writeln!(f, "[Fonts]")?;
             for font in &self.fonts {
                 writeln!(f, "{font}")?;
             }
         }
         if !self.graphics.is_empty() {
-            writeln!(f)?;
+            writeln!(f, "[Graphics]")?;
             for graphic in &self.graphics {
                 writeln!(f, "{graphic}")?;
             }
         }
         if !self.dialogue.is_empty() {
             writeln!(f, "[Events]")?;
-            writeln!(
-                f,
-                "Format: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text"
-            )?;
-            for event in &self.dialogue {
-                writeln!(f, "{event}")?;
-            }
-            for event in &self.pictures {
-                writeln!(f, "{event}")?;
-            }
-            for event in &self.sounds {
-                writeln!(f, "{event}")?;
-            }
-            for event in &self.movies {
-                writeln!(f, "{event}")?;
-            }
-            for event in &self.commands {
-                writeln!(f, "{event}")?;
+            let order = field_order(&self.events_format, &SSA_EVENT_FIELDS);
+            write_format_line(f, &self.events_format, &SSA_EVENT_FIELDS, order.is_some())?;
+            let events = self
+                .dialogue
+                .iter()
+                .chain(&self.pictures)
+                .chain(&self.sounds)
+                .chain(&self.movies)
+                .chain(&self.commands);
+            for event in events {
+                writeln!(
+                    f,
+                    "{}",
+                    values_from_standard_order(&event.to_string(), order.as_deref())
+                )?;
             }
         }
 
@@ -448,7 +633,7 @@ impl From<&TimedMicroDvdSubtitle> for SsaSubtitle {
                         margin_r: 0,
                         margin_v: 0,
                         effect: None,
-                        text: line.text.replace('|', "\\N"),
+                        text: mdvd_to_substation_formatting(line.text.as_str()),
                     })
                     .collect(),
             )
@@ -594,7 +779,21 @@ impl From<PlainSubtitle> for SsaSubtitle {
     }
 }
 
+impl IdentifiableEvent for SsaEvent {
+    fn identifier(&self) -> Option<String> {
+        None
+    }
+}
+
 impl TextEvent for SsaEvent {
+    fn remove_sdh(&mut self) {
+        self.text = remove_sdh_annotations(self.text.as_str(), "\\N");
+    }
+
+    fn mark_rtl(&mut self) {
+        self.text = mark_rtl_lines(self.text.as_str(), "\\N");
+    }
+
     fn unformatted_text(&self) -> Cow<'_, String> {
         let Ok((_, stripped)) = strip_formatting_tags(self.text.as_str()) else {
             return Cow::Borrowed(&self.text);
@@ -606,6 +805,10 @@ impl TextEvent for SsaEvent {
     fn as_plaintext(&self) -> Cow<'_, String> {
         Cow::Owned(self.unformatted_text().replace("\\N", "\n"))
     }
+
+    fn speaker(&self) -> Option<String> {
+        self.name.clone().filter(|name| !name.is_empty())
+    }
 }
 
 impl TimedEvent for SsaEvent {}
@@ -703,6 +906,9 @@ impl Display for SsaScriptInfo {
         if let Some(timer) = &self.timer {
             write!(f, "\nTimer: {timer}")?;
         }
+        for (key, value) in &self.extra {
+            write!(f, "\n{key}: {value}")?;
+        }
 
         Ok(())
     }
@@ -718,7 +924,7 @@ impl Display for SsaStyle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            "Style: {},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
             self.name,
             self.fontname,
             self.fontsize,
@@ -726,8 +932,8 @@ impl Display for SsaStyle {
             self.secondary_colour,
             self.tertiary_colour,
             self.back_colour,
-            self.bold,
-            self.italic,
+            format_reverse_bool(self.bold),
+            format_reverse_bool(self.italic),
             self.border_style,
             self.outline,
             self.shadow,
@@ -1,7 +1,4 @@
-use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader, Read},
-};
+use std::io::{BufReader, Read};
 
 use nom::{
     branch::alt,
@@ -13,12 +10,13 @@ use nom::{
 };
 
 use crate::{
+    parsing::lines,
     substation::{
         common::{
-            data::SubStationEventKind,
+            data::{field_order, values_to_standard_order, SubStationEventKind},
             parse::{
-                parse_category_header, parse_fonts, parse_graphics, parse_reverse_bool,
-                parse_timestamp, SubStationSection,
+                parse_category_header, parse_fonts, parse_format_line, parse_graphics,
+                parse_reverse_bool, parse_timestamp, SubStationSection,
             },
         },
         ssa::{SsaEvent, SsaScriptInfo, SsaStyle},
@@ -26,6 +24,8 @@ use crate::{
     Moment, SsaSubtitle,
 };
 
+use super::data::{SSA_EVENT_FIELDS, SSA_STYLE_FIELDS};
+
 #[derive(Debug)]
 enum SsaCategory<'a> {
     ScriptInfo((&'a str, &'a str)),
@@ -248,29 +248,33 @@ fn parse_nothing(input: &str) -> IResult<&str, SsaCategory> {
     fail(input)
 }
 
-fn build_script_info(data: &HashMap<String, String>) -> SsaScriptInfo {
-    SsaScriptInfo {
-        title: data.get("Title").map(std::clone::Clone::clone),
-        original_script: data.get("Original Script").map(std::clone::Clone::clone),
-        original_translation: data
-            .get("Original Translation")
-            .map(std::clone::Clone::clone),
-        original_editing: data.get("Original Editing").map(std::clone::Clone::clone),
-        original_timing: data.get("Original Timing").map(std::clone::Clone::clone),
-        synch_point: data.get("Synch Point").map(std::clone::Clone::clone),
-        script_updated_by: data.get("Script Updated By").map(std::clone::Clone::clone),
-        update_details: data.get("Update Details").map(std::clone::Clone::clone),
-        script_type: data.get("ScriptType").map(std::clone::Clone::clone),
-        collisions: data.get("Collisions").map(std::clone::Clone::clone),
-        play_res_y: data.get("PlayResY").map(std::clone::Clone::clone),
-        play_res_x: data.get("PlayResX").map(std::clone::Clone::clone),
-        play_depth: data.get("PlayDepth").map(std::clone::Clone::clone),
-        timer: data.get("Timer").map(std::clone::Clone::clone),
+fn build_script_info(data: Vec<(String, String)>) -> SsaScriptInfo {
+    let mut info = SsaScriptInfo::builder().build();
+    for (key, value) in data {
+        match key.as_str() {
+            "Title" => info.title = Some(value),
+            "Original Script" => info.original_script = Some(value),
+            "Original Translation" => info.original_translation = Some(value),
+            "Original Editing" => info.original_editing = Some(value),
+            "Original Timing" => info.original_timing = Some(value),
+            "Synch Point" => info.synch_point = Some(value),
+            "Script Updated By" => info.script_updated_by = Some(value),
+            "Update Details" => info.update_details = Some(value),
+            "ScriptType" => info.script_type = Some(value),
+            "Collisions" => info.collisions = Some(value),
+            "PlayResY" => info.play_res_y = Some(value),
+            "PlayResX" => info.play_res_x = Some(value),
+            "PlayDepth" => info.play_depth = Some(value),
+            "Timer" => info.timer = Some(value),
+            _ => info.extra.push((key, value)),
+        }
     }
+
+    info
 }
 
 pub(crate) fn parse_ssa<T: Read>(reader: BufReader<T>) -> SsaSubtitle {
-    let mut raw_script_info = HashMap::new();
+    let mut raw_script_info = Vec::new();
     let mut dialogue = Vec::new();
     let mut pictures = Vec::new();
     let mut sounds = Vec::new();
@@ -279,15 +283,43 @@ pub(crate) fn parse_ssa<T: Read>(reader: BufReader<T>) -> SsaSubtitle {
     let mut styles = Vec::new();
     let mut raw_graphics = Vec::new();
     let mut raw_fonts = Vec::new();
+    let mut styles_format = Vec::new();
+    let mut events_format = Vec::new();
+    let mut styles_order = None;
+    let mut events_order = None;
     let mut state = None;
-    for line in reader.lines() {
-        let Ok(line) = line else {
+    for line in lines(reader) {
+        let Ok(mut line) = line else {
             continue;
         };
         if let Ok((_, category)) = parse_category_header(line.as_str()) {
             state = Some(category);
             continue;
         }
+        if let Ok((_, fields)) = parse_format_line(line.as_str()) {
+            match state {
+                Some(SubStationSection::Styles) => {
+                    styles_order = field_order(&fields, &SSA_STYLE_FIELDS);
+                    styles_format = fields;
+                }
+                Some(SubStationSection::Events) => {
+                    events_order = field_order(&fields, &SSA_EVENT_FIELDS);
+                    events_format = fields;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        // Parsers expect fields in the standard order
+        let order = match state {
+            Some(SubStationSection::Styles) => styles_order.as_deref(),
+            Some(SubStationSection::Events) => events_order.as_deref(),
+            _ => None,
+        };
+        if let Some(order) = order {
+            line = values_to_standard_order(line.as_str(), order);
+        }
 
         let parse_fn = match state {
             Some(SubStationSection::Events) => parse_event,
@@ -309,11 +341,13 @@ pub(crate) fn parse_ssa<T: Read>(reader: BufReader<T>) -> SsaSubtitle {
                 SubStationEventKind::Sound => sounds.push(event),
                 SubStationEventKind::Movie => movies.push(event),
                 SubStationEventKind::Command => commands.push(event),
+                // Comment events are not parsed in .ssa files
+                SubStationEventKind::Comment => {}
             },
             SsaCategory::Fonts(font) => raw_fonts.push(font.to_string()),
             SsaCategory::Graphics(graphic) => raw_graphics.push(graphic.to_string()),
             SsaCategory::ScriptInfo((key, value)) => {
-                raw_script_info.insert(key.to_string(), value.to_string());
+                raw_script_info.push((key.to_string(), value.to_string()));
             }
             SsaCategory::Styles(style) => styles.push(style),
         }
@@ -330,7 +364,7 @@ pub(crate) fn parse_ssa<T: Read>(reader: BufReader<T>) -> SsaSubtitle {
     };
 
     SsaSubtitle::builder()
-        .script_info(build_script_info(&raw_script_info))
+        .script_info(build_script_info(raw_script_info))
         .dialogue(dialogue)
         .pictures(pictures)
         .sounds(sounds)
@@ -339,5 +373,7 @@ pub(crate) fn parse_ssa<T: Read>(reader: BufReader<T>) -> SsaSubtitle {
         .styles(styles)
         .fonts(fonts)
         .graphics(graphics)
+        .styles_format(styles_format)
+        .events_format(events_format)
         .build()
 }
@@ -0,0 +1,28 @@
+use std::fmt::Debug;
+
+use crate::Subtitle;
+
+/// Assert that a subtitle survives being serialized and parsed again.
+///
+/// `input` is parsed as `T`, serialized with its [`Display`](std::fmt::Display) implementation,
+/// and parsed again. The events of both parses must be equal.
+///
+/// # Panics
+///
+/// Panics if either parse fails, or if the events differ after the round trip.
+pub fn assert_roundtrip<T>(input: &str)
+where
+    T: Subtitle,
+    T::Err: Debug,
+    T::Event: PartialEq + Debug,
+{
+    let parsed = T::from_str(input).expect("failed to parse input");
+    let serialized = parsed.to_string();
+    let reparsed = T::from_str(serialized.as_str()).expect("failed to parse serialized subtitle");
+
+    assert_eq!(
+        parsed.events(),
+        reparsed.events(),
+        "events differ after round trip, serialized subtitle was:\n{serialized}"
+    );
+}
@@ -1,8 +1,10 @@
-use std::path::Path;
+use std::{path::Path, str::FromStr};
+
+use encoding_rs::Encoding;
 
 use crate::{
-    detection::detect_format_with_encoding, encoding::detect_file_encoding, AssSubtitle, Error,
-    SsaSubtitle, SubRipSubtitle, Subtitle, TimedMicroDvdSubtitle, WebVttSubtitle,
+    detection::detect_format_with_contents, encoding::read_file_with_encoding, AssSubtitle, Error,
+    ExportOptions, SsaSubtitle, SubRipSubtitle, Subtitle, TimedMicroDvdSubtitle, WebVttSubtitle,
 };
 
 /// Convenience interface for interacting with time-based subtitle files in a generic manner.
@@ -50,26 +52,39 @@ impl TimedSubtitleFile {
     /// - If an error is encountered while opening the file, returns [`Error::FileIoError`]
     /// - If the format cannot be successfully detected, returns [`Error::FormatUnknownError`]
     pub fn new(path: impl AsRef<Path>) -> Result<Self, Error> {
-        let encoding = detect_file_encoding(path.as_ref(), None).ok();
-        let format = detect_format_with_encoding(path.as_ref(), encoding)?;
+        Self::new_with_format(path).map(|(subtitle, _)| subtitle)
+    }
+
+    /// Automatically attempts to detect format using the file extension and file contents.
+    ///
+    /// Using the detected format, try to parse the given path and load its data.
+    /// Returns the detected format alongside the loaded data.
+    ///
+    /// # Errors
+    ///
+    /// - If an error is encountered while opening the file, returns [`Error::FileIoError`]
+    /// - If the format cannot be successfully detected, returns [`Error::FormatUnknownError`]
+    pub fn new_with_format(path: impl AsRef<Path>) -> Result<(Self, Format), Error> {
+        let text = read_file_with_encoding(path.as_ref(), None, None)?;
+        let format = detect_format_with_contents(path.as_ref(), || Ok(text.as_bytes()))?;
 
+        Self::parse_str(text.as_str(), format).map(|subtitle| (subtitle, format))
+    }
+
+    /// Parse the given text as the given format, without reading from the filesystem.
+    ///
+    /// Use [`detect_format_from_str`](crate::detect_format_from_str) first if the format is not known.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error produced by the [`FromStr`] implementation of the given format
+    pub fn parse_str(s: &str, format: Format) -> Result<Self, Error> {
         match format {
-            Format::Ass => {
-                AssSubtitle::from_path_with_encoding(path.as_ref(), encoding).map(Self::Ass)
-            }
-            Format::MicroDvd => {
-                TimedMicroDvdSubtitle::from_path_with_encoding(path.as_ref(), encoding)
-                    .map(Self::MicroDvd)
-            }
-            Format::Ssa => {
-                SsaSubtitle::from_path_with_encoding(path.as_ref(), encoding).map(Self::Ssa)
-            }
-            Format::SubRip => {
-                SubRipSubtitle::from_path_with_encoding(path.as_ref(), encoding).map(Self::SubRip)
-            }
-            Format::WebVtt => {
-                WebVttSubtitle::from_path_with_encoding(path.as_ref(), encoding).map(Self::WebVtt)
-            }
+            Format::Ass => AssSubtitle::from_str(s).map(Self::Ass),
+            Format::MicroDvd => TimedMicroDvdSubtitle::from_str(s).map(Self::MicroDvd),
+            Format::Ssa => SsaSubtitle::from_str(s).map(Self::Ssa),
+            Format::SubRip => SubRipSubtitle::from_str(s).map(Self::SubRip),
+            Format::WebVtt => WebVttSubtitle::from_str(s).map(Self::WebVtt),
         }
     }
 
@@ -102,4 +117,67 @@ impl TimedSubtitleFile {
             Self::WebVtt(data) => data.export(path.as_ref()),
         }
     }
+
+    /// Exports contents to file in the corresponding format, using the given options
+    ///
+    /// # Errors
+    ///
+    /// If an error is encountered while creating the file, returns [`Error::FileIoError`]
+    pub fn export_with_options(
+        &self,
+        path: impl AsRef<Path>,
+        options: ExportOptions,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Ass(data) => data.export_with_options(path.as_ref(), options),
+            Self::MicroDvd(data) => data.export_with_options(path.as_ref(), options),
+            Self::Ssa(data) => data.export_with_options(path.as_ref(), options),
+            Self::SubRip(data) => data.export_with_options(path.as_ref(), options),
+            Self::WebVtt(data) => data.export_with_options(path.as_ref(), options),
+        }
+    }
+
+    /// Exports contents to file in the corresponding format, encoded using the given encoding
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`Error::EncodingError`] if the subtitle contains characters which cannot be represented in the given encoding
+    /// - Returns [`Error::UnsupportedEncodingError`] if the encoding cannot be used for output (`replacement`)
+    /// - Returns [`Error::FileIoError`] if an error is encountered while creating the file
+    pub fn export_with_encoding(
+        &self,
+        path: impl AsRef<Path>,
+        encoding: &'static Encoding,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Ass(data) => data.export_with_encoding(path.as_ref(), encoding),
+            Self::MicroDvd(data) => data.export_with_encoding(path.as_ref(), encoding),
+            Self::Ssa(data) => data.export_with_encoding(path.as_ref(), encoding),
+            Self::SubRip(data) => data.export_with_encoding(path.as_ref(), encoding),
+            Self::WebVtt(data) => data.export_with_encoding(path.as_ref(), encoding),
+        }
+    }
+
+    /// Exports contents to file in the corresponding format, encoded using the given encoding.
+    ///
+    /// Characters which cannot be represented in the encoding are replaced,
+    /// see [`Subtitle::export_with_encoding_lossy`]. Returns whether any characters were replaced.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`Error::UnsupportedEncodingError`] if the encoding cannot be used for output (`replacement`)
+    /// - Returns [`Error::FileIoError`] if an error is encountered while creating the file
+    pub fn export_with_encoding_lossy(
+        &self,
+        path: impl AsRef<Path>,
+        encoding: &'static Encoding,
+    ) -> Result<bool, Error> {
+        match self {
+            Self::Ass(data) => data.export_with_encoding_lossy(path.as_ref(), encoding),
+            Self::MicroDvd(data) => data.export_with_encoding_lossy(path.as_ref(), encoding),
+            Self::Ssa(data) => data.export_with_encoding_lossy(path.as_ref(), encoding),
+            Self::SubRip(data) => data.export_with_encoding_lossy(path.as_ref(), encoding),
+            Self::WebVtt(data) => data.export_with_encoding_lossy(path.as_ref(), encoding),
+        }
+    }
 }
@@ -1,18 +1,23 @@
 use std::{
     fmt::Display,
-    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    str::FromStr,
 };
 
+use nom::{combinator::all_consuming, Parser};
+
+use crate::{subrip, substation, webvtt, Error};
+
 /// Moment in time, in milliseconds relative to the start of the media file
-#[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Clone, Copy)]
+#[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Clone, Copy, Default)]
 pub struct Moment(i64);
 
 /// Difference between two moments in milliseconds
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, Default)]
 pub struct TimeDelta(i64);
 
 /// Frame index of a video
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, Default)]
 pub struct Frame(i64);
 
 impl Moment {
@@ -46,28 +51,49 @@ impl Moment {
         self.0 / 10 % 100
     }
 
+    /// Convert to a timestamp of the form `HH:MM:SS{separator}{fraction}`, where the fractional
+    /// part of the second has exactly `decimals` digits.
+    ///
+    /// The moment is rounded to the nearest value representable with the given number of digits.
+    /// If `decimals` is 0, the separator and fractional part are omitted entirely.
+    #[must_use]
+    pub fn as_timestamp(&self, separator: char, decimals: usize) -> String {
+        let digits = u32::try_from(decimals).unwrap_or(u32::MAX);
+        let scale = 10_i64.pow(3_u32.saturating_sub(digits));
+        let rounded = Moment((self.0 + scale / 2).div_euclid(scale) * scale);
+        let timestamp = format!(
+            "{:02}:{:02}:{:02}",
+            rounded.hours(),
+            rounded.minutes(),
+            rounded.seconds()
+        );
+
+        if decimals == 0 {
+            return timestamp;
+        }
+
+        let fraction = rounded.ms() / scale;
+        if decimals > 3 {
+            format!(
+                "{timestamp}{separator}{fraction:03}{:0<1$}",
+                "",
+                decimals - 3
+            )
+        } else {
+            format!("{timestamp}{separator}{fraction:0decimals$}")
+        }
+    }
+
     /// Convert to .vtt timestamp format (`HH:MM:SS.0ms`)
     #[must_use]
     pub fn as_vtt_timestamp(&self) -> String {
-        format!(
-            "{:02}:{:02}:{:02}.{:03}",
-            self.hours(),
-            self.minutes(),
-            self.seconds(),
-            self.ms()
-        )
+        self.as_timestamp('.', 3)
     }
 
     /// Convert to .srt timestamp format (`HH:MM:SS,0ms`)
     #[must_use]
     pub fn as_srt_timestamp(&self) -> String {
-        format!(
-            "{:02}:{:02}:{:02},{:03}",
-            self.hours(),
-            self.minutes(),
-            self.seconds(),
-            self.ms()
-        )
+        self.as_timestamp(',', 3)
     }
 
     /// Convert to .ass timestamp format (`H:MM:SS,cs`)
@@ -82,14 +108,71 @@ impl Moment {
         )
     }
 
-    pub(crate) fn from_timestamp(
-        hours: i64,
-        minutes: i64,
-        seconds: i64,
-        milliseconds: i64,
-    ) -> Moment {
+    /// Parse a timestamp in any of the supported formats.
+    ///
+    /// SubRip (`HH:MM:SS,mmm`), SubStation (`H:MM:SS.cc`) and WebVTT (`HH:MM:SS.mmm` or
+    /// `MM:SS.mmm`) timestamps are tried in turn, and the first one to match the whole string is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TimestampParseError`] if the string does not match any supported format.
+    pub fn parse_any(s: &str) -> Result<Moment, Error> {
+        all_consuming(subrip::parse::parse_timestamp)
+            .parse(s)
+            .or_else(|_| all_consuming(substation::common::parse::parse_timestamp).parse(s))
+            .or_else(|_| all_consuming(webvtt::parse::parse_timestamp).parse(s))
+            .map(|(_, moment)| moment)
+            .map_err(|_| Error::TimestampParseError(s.to_string()))
+    }
+
+    /// Create a moment from the components of a timestamp.
+    ///
+    /// Components are not required to be within their usual ranges, so for example 61 seconds
+    /// is equivalent to 1 minute and 1 second.
+    #[must_use]
+    pub fn from_timestamp(hours: i64, minutes: i64, seconds: i64, milliseconds: i64) -> Moment {
         (hours * 60 * 60 * 1000 + minutes * 60 * 1000 + seconds * 1000 + milliseconds).into()
     }
+
+    /// Create a moment from a number of seconds, rounded to the nearest millisecond.
+    ///
+    /// See [`TimeDelta::from_seconds_f64`] for details on how rounding is done.
+    #[must_use]
+    pub fn from_seconds_f64(seconds: f64) -> Moment {
+        Moment(seconds_f64_to_ms(seconds))
+    }
+
+    /// Number of seconds, including the fractional part
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_seconds_f64(&self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+
+    /// Linearly interpolate between two moments, rounded to the nearest millisecond.
+    ///
+    /// `t` is clamped to the range `[0, 1]`, so that 0 gives `a` and 1 gives `b`.
+    /// `b` may come before `a`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn lerp(a: Moment, b: Moment, t: f64) -> Moment {
+        let t = t.clamp(0.0, 1.0);
+
+        Moment(a.0 + ((b.0 - a.0) as f64 * t).round() as i64)
+    }
+}
+
+impl FromStr for Moment {
+    type Err = Error;
+
+    /// Parse a timestamp of the form `HH:MM:SS,mmm`, `HH:MM:SS.mmm` or `MM:SS.mmm`.
+    ///
+    /// The hour component does not need a leading zero. Out of range components, such as
+    /// `00:00:61,000`, are accepted and normalized as in [`Moment::from_timestamp`].
+    /// See [`Moment::parse_any`] for the full list of accepted formats.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Moment::parse_any(s)
+    }
 }
 
 impl Sub for Moment {
@@ -132,6 +215,14 @@ impl Div<i64> for Moment {
     }
 }
 
+impl Neg for Moment {
+    type Output = Moment;
+
+    fn neg(self) -> Self::Output {
+        Moment(-self.0)
+    }
+}
+
 impl AddAssign<TimeDelta> for Moment {
     fn add_assign(&mut self, rhs: TimeDelta) {
         self.0 += rhs.0;
@@ -156,6 +247,44 @@ impl From<Moment> for i64 {
     }
 }
 
+impl TimeDelta {
+    /// Create a time difference from a number of seconds, rounded to the nearest millisecond.
+    ///
+    /// To avoid floating-point representation error affecting the result, the value is first rounded
+    /// to the nearest microsecond, and then to the nearest millisecond with halves rounded away from zero.
+    /// For example, `1.5005` seconds becomes 1501 milliseconds.
+    #[must_use]
+    pub fn from_seconds_f64(seconds: f64) -> TimeDelta {
+        TimeDelta(seconds_f64_to_ms(seconds))
+    }
+
+    /// Number of seconds, including the fractional part
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_seconds_f64(&self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+
+    /// Number of frames spanned by the time difference at the given framerate, rounded to the nearest frame
+    #[must_use]
+    pub fn as_frames(&self, framerate: f32) -> i64 {
+        moment_to_frame(Moment(self.0), framerate).into()
+    }
+
+    /// Format as a signed number of frames at the given framerate, e.g. `+12 frames` or `-1 frame`
+    #[must_use]
+    pub fn format_frames(&self, framerate: f32) -> String {
+        let frames = self.as_frames(framerate);
+        let unit = if frames.abs() == 1 { "frame" } else { "frames" };
+
+        if frames < 0 {
+            format!("{frames} {unit}")
+        } else {
+            format!("+{frames} {unit}")
+        }
+    }
+}
+
 impl Add<Moment> for TimeDelta {
     type Output = Moment;
 
@@ -204,6 +333,14 @@ impl Div<i64> for TimeDelta {
     }
 }
 
+impl Neg for TimeDelta {
+    type Output = TimeDelta;
+
+    fn neg(self) -> Self::Output {
+        TimeDelta(-self.0)
+    }
+}
+
 impl AddAssign for TimeDelta {
     fn add_assign(&mut self, rhs: TimeDelta) {
         self.0 += rhs.0;
@@ -252,6 +389,28 @@ impl From<Frame> for i64 {
     }
 }
 
+impl Display for Moment {
+    /// Format as `HH:MM:SS.mmm`, prefixed with `-` if the moment is negative
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 < 0 {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", Moment(self.0.saturating_abs()).as_vtt_timestamp())
+    }
+}
+
+impl Display for TimeDelta {
+    /// Format as `+HH:MM:SS.mmm` or `-HH:MM:SS.mmm`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < 0 { '-' } else { '+' };
+        write!(
+            f,
+            "{sign}{}",
+            Moment(self.0.saturating_abs()).as_vtt_timestamp()
+        )
+    }
+}
+
 impl Display for Frame {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -262,6 +421,13 @@ pub(crate) fn moment_to_frame(moment: Moment, framerate: f32) -> Frame {
     Frame(((i64::from(moment) as f32) * framerate / 1000.0).round() as i64)
 }
 
+#[allow(clippy::cast_possible_truncation)]
+fn seconds_f64_to_ms(seconds: f64) -> i64 {
+    let microseconds = (seconds * 1_000_000.0).round();
+
+    (microseconds / 1000.0).round() as i64
+}
+
 pub(crate) fn frame_to_moment(frame: Frame, framerate: f32) -> Moment {
     Moment(((frame.0 * 1000) as f32 / framerate).round() as i64)
 }
@@ -1,15 +1,23 @@
 use std::{
     borrow::Cow,
+    collections::HashMap,
     fmt::Display,
     fs::File,
-    io::{BufWriter, Write},
+    io::{BufWriter, Read, Write},
     path::Path,
     str::FromStr,
 };
 
 use encoding_rs::Encoding;
 
-use crate::{errors::Error, Moment, TimeDelta};
+use crate::{
+    encoding::{decode_file, encode_text, encode_text_lossy, parse_from_reader},
+    errors::Error,
+    punctuation::normalize_punctuation,
+    sdh::remove_sdh_annotations,
+    ConversionLoss, ExportOptions, Moment, PunctuationOptions, ReadingSpeedStats, SubtitleStats,
+    TimeDelta,
+};
 
 /// Base trait for all subtitle implementations.
 pub trait Subtitle: Display + FromStr {
@@ -26,6 +34,21 @@ pub trait Subtitle: Display + FromStr {
         Self::from_path_with_encoding(path, None)
     }
 
+    /// Load subtitle from given path, returning it along with the encoding used to read the file.
+    /// The encoding is detected from the file contents in the same way as [`Subtitle::from_path`].
+    ///
+    /// # Errors
+    ///
+    /// If an error is encountered while opening the file, returns [`Error::FileIoError`]
+    fn from_path_detecting(path: impl AsRef<Path>) -> Result<(Self, &'static Encoding), Error>
+    where
+        Self::Err: Into<Error>,
+    {
+        let (text, encoding) = decode_file(path.as_ref(), None, Some(30))?;
+
+        Ok((Self::from_str(text.as_str()).map_err(Into::into)?, encoding))
+    }
+
     /// Load subtitle format from path using the given encoding
     ///
     /// # Errors
@@ -36,6 +59,27 @@ pub trait Subtitle: Display + FromStr {
         encoding: Option<&'static Encoding>,
     ) -> Result<Self, Error>;
 
+    /// Load subtitle from the given reader, such as standard input or a network stream.
+    ///
+    /// Text is read as UTF-8, unless it starts with a byte order mark indicating another encoding.
+    /// Invalid UTF-8 sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    /// By default, all text is read before being parsed using [`FromStr`].
+    ///
+    /// # Errors
+    ///
+    /// If an error is encountered while reading, returns [`Error::FileIoError`]
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error>
+    where
+        Self::Err: Into<Error>,
+    {
+        let text = parse_from_reader(reader, |reader| {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).map(|_| text)
+        })??;
+
+        Self::from_str(text.as_str()).map_err(Into::into)
+    }
+
     /// Get list of events as a slice
     fn events(&self) -> &[Self::Event];
 
@@ -58,12 +102,123 @@ pub trait Subtitle: Display + FromStr {
     /// Returns [`Error::FileIoError`] if method fails to create file at the specified path
     fn export(&self, path: impl AsRef<Path>) -> Result<(), Error> {
         let file = File::create(path)?;
-        let mut writer = BufWriter::new(file);
 
-        Ok(write!(writer, "{self}")?)
+        self.to_writer(file)
+    }
+
+    /// Write subtitles to the given writer, such as a [`Vec<u8>`] or a network stream.
+    /// Writes are buffered, so the writer does not need to be buffered itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::FileIoError`] if writing to the writer fails
+    fn to_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = BufWriter::new(writer);
+        write!(writer, "{self}")?;
+
+        Ok(writer.flush()?)
+    }
+
+    /// Write subtitles to file at the given path, using the given options.
+    ///
+    /// Only the bytes written are affected by the options, not the subtitle itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::FileIoError`] if method fails to create file at the specified path
+    fn export_with_options(
+        &self,
+        path: impl AsRef<Path>,
+        options: ExportOptions,
+    ) -> Result<(), Error> {
+        Ok(std::fs::write(path, options.encode(&self.to_string()))?)
+    }
+
+    /// Write subtitles to file at the given path, encoded using the given encoding.
+    ///
+    /// UTF-16 output starts with a byte order mark.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`Error::EncodingError`] if the subtitle contains characters which cannot be represented in the given encoding
+    /// - Returns [`Error::UnsupportedEncodingError`] if the encoding cannot be used for output (`replacement`)
+    /// - Returns [`Error::FileIoError`] if method fails to create file at the specified path
+    fn export_with_encoding(
+        &self,
+        path: impl AsRef<Path>,
+        encoding: &'static Encoding,
+    ) -> Result<(), Error> {
+        let text = self.to_string();
+        let bytes = encode_text(text.as_str(), encoding)?;
+
+        Ok(std::fs::write(path, bytes)?)
+    }
+
+    /// Write subtitles to file at the given path, encoded using the given encoding.
+    ///
+    /// Unlike [`Subtitle::export_with_encoding`], characters which cannot be represented in the encoding
+    /// are replaced with HTML numeric character references (e.g. `&#1605;`) instead of causing an error.
+    /// Returns whether any characters were replaced. UTF-16 output starts with a byte order mark.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`Error::UnsupportedEncodingError`] if the encoding cannot be used for output (`replacement`)
+    /// - Returns [`Error::FileIoError`] if method fails to create file at the specified path
+    fn export_with_encoding_lossy(
+        &self,
+        path: impl AsRef<Path>,
+        encoding: &'static Encoding,
+    ) -> Result<bool, Error> {
+        let text = self.to_string();
+        let (bytes, lossy) = encode_text_lossy(text.as_str(), encoding)?;
+        std::fs::write(path, bytes)?;
+
+        Ok(lossy)
     }
 }
 
+/// Conversion between subtitle formats which fails instead of silently dropping information.
+///
+/// Use the [`From`] implementations instead when losing information is acceptable.
+pub trait TryConvert<T> {
+    /// Try to convert into another subtitle format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConversionLoss`] describing the information which cannot be represented in the target format.
+    fn try_convert(&self) -> Result<T, ConversionLoss>;
+}
+
+pub(crate) fn split_events_at<T>(subtitle: &mut T, at: Moment) -> T
+where
+    T: RetainEvents + Clone,
+    T::Event: TimedEvent,
+{
+    let mut tail = subtitle.clone();
+
+    subtitle.retain_events(|event| event.start() < at);
+    for event in subtitle.events_mut() {
+        if event.end() > at {
+            event.set_end(at);
+        }
+    }
+
+    tail.retain_events(|event| event.start() >= at || event.end() > at);
+    for event in tail.events_mut() {
+        if event.start() < at {
+            event.set_start(at);
+        }
+    }
+
+    tail
+}
+
+/// Trait for subtitle formats whose events can be removed
+pub trait RetainEvents: Subtitle {
+    /// Retain only the events for which the given predicate returns `true`, removing all others
+    fn retain_events(&mut self, f: impl FnMut(&Self::Event) -> bool);
+}
+
 /// Trait representing textual subtitle formats
 pub trait TextSubtitle: Subtitle
 where
@@ -75,6 +230,76 @@ where
             event.strip_formatting();
         }
     }
+
+    /// Remove hearing impaired annotations, such as sound descriptions (`[door slams]`, `(LAUGHS)`)
+    /// and speaker labels (`JOHN:`), from all events.
+    ///
+    /// Events which are left without any text are removed.
+    fn remove_sdh(&mut self)
+    where
+        Self: RetainEvents,
+    {
+        for event in self.events_mut() {
+            event.remove_sdh();
+        }
+        self.retain_events(|event| !event.as_plaintext().trim().is_empty());
+    }
+
+    /// Prepend a right-to-left mark (`U+200F`) to each line of text which does not already start with one,
+    /// so that right-to-left text is displayed in the correct order by players which do not detect it automatically.
+    fn mark_rtl(&mut self) {
+        for event in self.events_mut() {
+            event.mark_rtl();
+        }
+    }
+
+    /// Remove all Unicode bidirectional formatting characters, such as right-to-left marks, from the text of each event
+    fn strip_bidi_marks(&mut self) {
+        for event in self.events_mut() {
+            let text = event.text();
+            if text.contains(is_bidi_mark) {
+                event.set_text(text.replace(is_bidi_mark, ""));
+            }
+        }
+    }
+
+    /// Convert quotation marks and ellipses in the text of each event to the styles given in `options`,
+    /// such as replacing `…` with `...` or straight quotes with curly quotes.
+    ///
+    /// Formatting tags are left untouched. When converting to curly quotes, whether a quote opens or closes
+    /// is guessed from the preceding character, and apostrophes within words become `’`.
+    fn normalize_punctuation(&mut self, options: PunctuationOptions) {
+        for event in self.events_mut() {
+            let text = event.text();
+            let normalized = normalize_punctuation(text.as_str(), options);
+            if normalized != text {
+                event.set_text(normalized);
+            }
+        }
+    }
+}
+
+const RIGHT_TO_LEFT_MARK: char = '\u{200F}';
+
+/// Prepend a right-to-left mark to each line of text, where lines are separated by `line_break`
+pub(crate) fn mark_rtl_lines(text: &str, line_break: &str) -> String {
+    text.split(line_break)
+        .map(|line| {
+            if line.starts_with(RIGHT_TO_LEFT_MARK) {
+                line.to_string()
+            } else {
+                format!("{RIGHT_TO_LEFT_MARK}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(line_break)
+}
+
+fn is_bidi_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'
+    )
 }
 
 /// Time-based subtitle
@@ -91,6 +316,395 @@ where
             event.shift(delta);
         }
     }
+
+    /// Find the event closest to the given moment, along with its index.
+    ///
+    /// The distance to an event is zero if the moment is between its start and end times,
+    /// and otherwise the time to its nearest boundary. Ties are broken by choosing the earlier index.
+    /// Returns `None` if there are no events.
+    fn nearest_event(&self, moment: Moment) -> Option<(usize, &Self::Event)> {
+        self.events().iter().enumerate().min_by_key(|(_, event)| {
+            if moment < event.start() {
+                event.start() - moment
+            } else if moment > event.end() {
+                moment - event.end()
+            } else {
+                TimeDelta::from(0)
+            }
+        })
+    }
+
+    /// Latest end time of any event, or `None` if there are no events.
+    ///
+    /// Events do not need to be sorted.
+    fn last_end(&self) -> Option<Moment> {
+        self.events().iter().map(TimedEventInterface::end).max()
+    }
+
+    /// Time from the earliest start of any event to the latest end of any event,
+    /// or `None` if there are no events.
+    ///
+    /// Events do not need to be sorted.
+    fn total_duration(&self) -> Option<TimeDelta> {
+        let first_start = self.events().iter().map(TimedEventInterface::start).min()?;
+
+        self.last_end().map(|end| end - first_start)
+    }
+
+    /// Split the subtitle at the given moment, keeping events before `at` and returning a new
+    /// subtitle containing the events after `at`.
+    ///
+    /// Events which start before and end after `at` are split into two, with the first part ending
+    /// at `at` and the second part starting at `at`. Events in the returned subtitle are not shifted;
+    /// use [`TimedSubtitle::shift`] to make them relative to `at`.
+    #[must_use]
+    fn split_at(&mut self, at: Moment) -> Self
+    where
+        Self: RetainEvents + Clone,
+    {
+        split_events_at(self, at)
+    }
+
+    /// Split the subtitle at the given moment like [`TimedSubtitle::split_at`], and shift the
+    /// returned subtitle so that `at` becomes zero, e.g. when splitting a subtitle for a two-part video.
+    #[must_use]
+    fn split_at_rebased(&mut self, at: Moment) -> Self
+    where
+        Self: RetainEvents + Clone,
+    {
+        let mut tail = self.split_at(at);
+        tail.shift(Moment::from(0) - at);
+
+        tail
+    }
+
+    /// Sort events in chronological order, by start time and then by end time.
+    ///
+    /// The sort is stable, so events with identical timings keep their relative order.
+    /// For [`SubRipSubtitle`](crate::SubRipSubtitle), line numbers are not updated;
+    /// call [`renumber`](crate::SubRipSubtitle::renumber) afterwards if needed.
+    fn sort_by_time(&mut self) {
+        self.events_mut()
+            .sort_by_key(|event| (event.start(), event.end()));
+    }
+
+    /// Remove overlaps between events, so that each event ends 1ms before the next one starts.
+    ///
+    /// See [`TimedSubtitle::fix_overlaps_with_gap`] for details.
+    fn fix_overlaps(&mut self) {
+        self.fix_overlaps_with_gap(TimeDelta::from(1));
+    }
+
+    /// Remove overlaps between events, so that each event ends at least `gap` before the next one starts.
+    ///
+    /// Events are first sorted by time. Events which extend too close to or past the start of the next
+    /// event are shortened. If an event would be left with no duration, such as when another event
+    /// is entirely contained within it, the next event is shifted to start after it instead.
+    fn fix_overlaps_with_gap(&mut self, gap: TimeDelta) {
+        self.sort_by_time();
+
+        let events = self.events_mut();
+        for i in 1..events.len() {
+            let (start, end) = (events[i - 1].start(), events[i - 1].end());
+            let next_start = events[i].start();
+            let limit = next_start - gap;
+
+            if end <= limit {
+                continue;
+            }
+
+            if limit > start {
+                events[i - 1].set_end(limit);
+            } else {
+                events[i].shift(end + gap - next_start);
+            }
+        }
+    }
+
+    /// Extend all events, starting `lead_in` earlier and ending `lead_out` later.
+    ///
+    /// Padding never moves an event before zero, or into a neighbouring event: each event is
+    /// extended at most until the start of the next one, and then at most back to the (already
+    /// extended) end of the previous one. Events which already overlap are left as they are.
+    /// Neighbours are determined by start time, and the order of events is unchanged.
+    fn pad(&mut self, lead_in: TimeDelta, lead_out: TimeDelta) {
+        let events = self.events_mut();
+        let mut order: Vec<usize> = (0..events.len()).collect();
+        order.sort_by_key(|&i| (events[i].start(), events[i].end()));
+
+        for pair in order.windows(2) {
+            let (current, next) = (pair[0], pair[1]);
+            let end = events[current].end();
+            let limit = events[next].start().max(end);
+            events[current].set_end((end + lead_out).min(limit));
+        }
+        if let Some(&last) = order.last() {
+            let end = events[last].end();
+            events[last].set_end(end + lead_out);
+        }
+
+        let mut previous_end = None;
+        for &i in &order {
+            let (start, end) = (events[i].start(), events[i].end());
+            let mut padded = (start - lead_in).max(Moment::from(0));
+            if let Some(limit) = previous_end {
+                padded = padded.max(start.min(limit));
+            }
+            events[i].set_start(padded.min(start));
+            previous_end = Some(end);
+        }
+    }
+
+    /// Multiply the start and end times of all events by `factor`, rounding to the nearest millisecond.
+    ///
+    /// This is useful for changing playback speed, e.g. a factor of `23.976 / 25.0` for PAL speedup.
+    /// Times which would become negative are clamped to zero.
+    fn rescale(&mut self, factor: f64) {
+        self.rescale_around(factor, Moment::from(0));
+    }
+
+    /// Scale the start and end times of all events by `factor`, relative to `anchor` instead of zero.
+    ///
+    /// Events at `anchor` do not move, while other events move away from or towards it.
+    /// Times which would become negative are clamped to zero.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn rescale_around(&mut self, factor: f64, anchor: Moment) {
+        let scale = |moment: Moment| {
+            let offset = i64::from(moment - anchor) as f64 * factor;
+            Moment::from((i64::from(anchor) + offset.round() as i64).max(0))
+        };
+
+        for event in self.events_mut() {
+            let (start, end) = (scale(event.start()), scale(event.end()));
+            event.set_start(start);
+            event.set_end(end);
+        }
+    }
+
+    /// Swap the start and end times of any events which end before they start.
+    ///
+    /// Returns the number of events which were fixed.
+    fn fix_swapped_timings(&mut self) -> usize {
+        let mut fixed = 0;
+        for event in self.events_mut() {
+            let (start, end) = (event.start(), event.end());
+            if start > end {
+                event.set_start(end);
+                event.set_end(start);
+                fixed += 1;
+            }
+        }
+
+        fixed
+    }
+
+    /// Estimate the constant offset which best aligns the events of this subtitle with those of `reference`,
+    /// such as a translation of the same video which is correctly timed.
+    ///
+    /// Every pair of start times from the two subtitles votes for the offset between them, with votes
+    /// grouped into 100ms buckets. The offset is the median of the votes near the most popular bucket,
+    /// so events do not need to correspond one-to-one. Returns zero if either subtitle has no events.
+    fn estimate_offset<R>(&self, reference: &R) -> TimeDelta
+    where
+        R: TimedSubtitle,
+        R::Event: TimedEvent,
+    {
+        const RESOLUTION: i64 = 100;
+
+        let offsets = || {
+            reference.events().iter().flat_map(|target| {
+                self.events()
+                    .iter()
+                    .map(move |event| i64::from(target.start() - event.start()))
+            })
+        };
+
+        let mut votes: HashMap<i64, usize> = HashMap::new();
+        for offset in offsets() {
+            *votes.entry(offset.div_euclid(RESOLUTION)).or_default() += 1;
+        }
+        let Some(bucket) = votes
+            .into_iter()
+            .max_by_key(|(bucket, count)| (*count, std::cmp::Reverse(bucket.abs())))
+            .map(|(bucket, _)| bucket)
+        else {
+            return TimeDelta::from(0);
+        };
+
+        let mut nearby: Vec<i64> = offsets()
+            .filter(|offset| (offset.div_euclid(RESOLUTION) - bucket).abs() <= 1)
+            .collect();
+        nearby.sort_unstable();
+
+        TimeDelta::from(nearby[nearby.len() / 2])
+    }
+
+    /// Shift all events by the offset which best aligns them with the events of `reference`.
+    ///
+    /// See [`TimedSubtitle::estimate_offset`] for how the offset is found.
+    /// Returns the offset which was applied.
+    fn sync_to<R>(&mut self, reference: &R) -> TimeDelta
+    where
+        R: TimedSubtitle,
+        R::Event: TimedEvent,
+    {
+        let offset = self.estimate_offset(reference);
+        self.shift(offset);
+
+        offset
+    }
+
+    /// Count the number of events active in each consecutive time bucket of the given size,
+    /// starting from zero and ending with the bucket containing the end of the last event.
+    ///
+    /// An event is counted in every bucket it overlaps. Returns an empty list if there are no events,
+    /// or if the bucket size is not positive.
+    fn event_density(&self, bucket: TimeDelta) -> Vec<usize> {
+        let size = i64::from(bucket);
+        let Some(last_end) = self
+            .events()
+            .iter()
+            .map(|event| i64::from(event.end()))
+            .max()
+        else {
+            return Vec::new();
+        };
+        if size <= 0 || last_end <= 0 {
+            return Vec::new();
+        }
+
+        let bucket_count = usize::try_from((last_end + size - 1) / size).unwrap_or_default();
+        let mut density = vec![0; bucket_count];
+        for event in self.events() {
+            let (start, end) = (i64::from(event.start()).max(0), i64::from(event.end()));
+            if end <= start {
+                continue;
+            }
+
+            let first = usize::try_from(start / size).unwrap_or_default();
+            let last = usize::try_from((end - 1) / size).unwrap_or_default();
+            for count in &mut density[first..=last] {
+                *count += 1;
+            }
+        }
+
+        density
+    }
+
+    /// Calculate reading speed statistics, in characters per second, across all events.
+    ///
+    /// Characters are counted from the plaintext of each event, excluding line breaks.
+    /// Events with a non-positive duration are ignored.
+    /// Events with a reading speed greater than `threshold` are counted in `events_over`.
+    #[allow(clippy::cast_precision_loss)]
+    fn reading_speed_stats(&self, threshold: f64) -> ReadingSpeedStats
+    where
+        <Self as Subtitle>::Event: TextEvent,
+    {
+        let speeds: Vec<f64> = self
+            .events()
+            .iter()
+            .filter(|event| i64::from(event.duration()) > 0)
+            .map(|event| {
+                let characters = event
+                    .as_plaintext()
+                    .chars()
+                    .filter(|c| *c != '\n' && *c != '\r')
+                    .count();
+
+                characters as f64 * 1000.0 / i64::from(event.duration()) as f64
+            })
+            .collect();
+
+        if speeds.is_empty() {
+            return ReadingSpeedStats::default();
+        }
+
+        ReadingSpeedStats {
+            avg_cps: speeds.iter().sum::<f64>() / speeds.len() as f64,
+            max_cps: speeds.iter().copied().fold(0.0, f64::max),
+            events_over: speeds.iter().filter(|cps| **cps > threshold).count(),
+        }
+    }
+
+    /// Get a transcript of the subtitle, as a list of lines of plaintext, each with its speaker
+    /// (if known), start time and end time.
+    ///
+    /// Lines are in the same order as the events of the subtitle. Text is trimmed of leading and
+    /// trailing whitespace, and events without any text are skipped.
+    fn to_dialogue_lines(&self) -> Vec<(Option<String>, Moment, Moment, String)>
+    where
+        <Self as Subtitle>::Event: TextEvent,
+    {
+        self.events()
+            .iter()
+            .filter_map(|event| {
+                let text = event.as_plaintext();
+                let text = text.trim();
+
+                (!text.is_empty()).then(|| {
+                    (
+                        event.speaker(),
+                        event.start(),
+                        event.end(),
+                        text.to_string(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Calculate summary statistics for the subtitle, such as the number of events,
+    /// reading speeds, and the number of overlapping events.
+    fn stats(&self) -> SubtitleStats
+    where
+        <Self as Subtitle>::Event: TextEvent,
+    {
+        let events = self.events();
+        let reading_speed = self.reading_speed_stats(f64::INFINITY);
+
+        let total_duration = self.total_duration().unwrap_or(TimeDelta::from(0));
+
+        let display_time = events
+            .iter()
+            .fold(TimeDelta::from(0), |total, event| total + event.duration());
+
+        let longest_line = events
+            .iter()
+            .flat_map(|event| {
+                event
+                    .as_plaintext()
+                    .lines()
+                    .map(|line| line.chars().count())
+                    .collect::<Vec<_>>()
+            })
+            .max()
+            .unwrap_or_default();
+
+        let mut timings: Vec<(Moment, Moment)> = events
+            .iter()
+            .map(|event| (event.start(), event.end()))
+            .collect();
+        timings.sort();
+        let mut overlaps = 0;
+        let mut latest_end: Option<Moment> = None;
+        for (start, end) in timings {
+            if latest_end.is_some_and(|latest| start < latest) {
+                overlaps += 1;
+            }
+            latest_end = latest_end.max(Some(end));
+        }
+
+        SubtitleStats {
+            event_count: events.len(),
+            total_duration,
+            display_time,
+            avg_cps: reading_speed.avg_cps,
+            max_cps: reading_speed.max_cps,
+            longest_line,
+            overlaps,
+        }
+    }
 }
 
 /// Trait offering helper functions for textual subtitle events
@@ -100,6 +714,20 @@ pub trait TextEvent: TextEventInterface {
         self.set_text(self.unformatted_text().into_owned());
     }
 
+    /// Remove hearing impaired annotations from event text.
+    ///
+    /// Sound descriptions in square brackets are always removed, while those in parentheses are
+    /// removed only if they make up a whole line or are written in capitals.
+    /// Speaker labels in capitals at the start of a line (e.g. `JOHN:`) are also removed.
+    fn remove_sdh(&mut self) {
+        self.set_text(remove_sdh_annotations(self.text().as_str(), "\n"));
+    }
+
+    /// Prepend a right-to-left mark (`U+200F`) to each line of event text which does not already start with one
+    fn mark_rtl(&mut self) {
+        self.set_text(mark_rtl_lines(self.text().as_str(), "\n"));
+    }
+
     /// Get text content with all formatting tags removed
     fn unformatted_text(&self) -> Cow<'_, String>;
 
@@ -110,6 +738,12 @@ pub trait TextEvent: TextEventInterface {
     fn as_plaintext(&self) -> Cow<'_, String> {
         self.unformatted_text()
     }
+
+    /// Name of the speaker of the event, for formats which record one, such as the name field of SubStation events.
+    /// Returns `None` if the event has no speaker or the format does not support them.
+    fn speaker(&self) -> Option<String> {
+        None
+    }
 }
 
 /// Interface for getting/modifying textual subtitle event fields.
@@ -142,6 +776,13 @@ pub trait TimedEvent: TimedEventInterface {
     }
 }
 
+/// Identifier of a subtitle event, for formats which have such a concept.
+pub trait IdentifiableEvent {
+    /// Identifier of event, such as the line number of a SubRip event or the identifier of a WebVTT cue.
+    /// Returns `None` if the event has no identifier or the format does not support them.
+    fn identifier(&self) -> Option<String>;
+}
+
 /// Interface for interacting with timed events.
 /// Required for implementation of [`TimedEvent`].
 pub trait TimedEventInterface {
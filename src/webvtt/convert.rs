@@ -8,6 +8,8 @@ use nom::{
 
 use crate::parsing::{discard, html_tag};
 
+use super::parse::cue_setting;
+
 fn convert_to_ass_tag(input: &str) -> IResult<&str, &str> {
     alt((
         value("{\\b1}", tag("<b>")),
@@ -61,3 +63,79 @@ pub(crate) fn strip_html_tags(input: &str) -> IResult<&str, String> {
     )
     .parse(input)
 }
+
+/// Convert the `line` and `align` cue settings to a SubStation numpad-style alignment (as used by `\an`).
+///
+/// Cues without settings are placed at the bottom centre, which corresponds to alignment 2.
+pub(crate) fn vtt_settings_to_ass_alignment(settings: &str) -> i64 {
+    let row = match cue_setting(settings, "line").and_then(|line| line.split(',').next()) {
+        Some(line) if line.ends_with('%') => match line.trim_end_matches('%').parse::<f64>() {
+            Ok(percent) if percent < 100.0 / 3.0 => 2,
+            Ok(percent) if percent < 200.0 / 3.0 => 1,
+            _ => 0,
+        },
+        // Positive line numbers count from the top of the video, negative ones from the bottom
+        Some(line) => match line.parse::<i64>() {
+            Ok(number) if number >= 0 => 2,
+            _ => 0,
+        },
+        None => 0,
+    };
+
+    let column = match cue_setting(settings, "align") {
+        Some("start" | "left") => 1,
+        Some("end" | "right") => 3,
+        _ => 2,
+    };
+
+    row * 3 + column
+}
+
+fn push_voice_span(
+    spans: &mut Vec<(Option<String>, String)>,
+    speaker: Option<&String>,
+    text: &mut String,
+) {
+    let trimmed = text.trim();
+    if !trimmed.is_empty() {
+        spans.push((speaker.cloned(), trimmed.to_string()));
+    }
+    text.clear();
+}
+
+/// Split text into segments according to voice spans (`<v Name>`, `<v.class Name>`),
+/// pairing each segment with the name of its speaker. Other tags are removed unless `keep_tags` is set.
+///
+/// A voice span lasts until a closing `</v>` tag, the next voice span, or the end of the text.
+pub(crate) fn voice_spans(input: &str, keep_tags: bool) -> Vec<(Option<String>, String)> {
+    let mut spans = Vec::new();
+    let mut speaker = None;
+    let mut text = String::new();
+    let mut remaining = input;
+
+    while let Some(c) = remaining.chars().next() {
+        if let Ok((rest, tag)) = html_tag(remaining) {
+            if let Some(annotation) = tag.strip_prefix('v').filter(|annotation| {
+                annotation.starts_with(|c: char| c == '.' || c.is_whitespace())
+            }) {
+                push_voice_span(&mut spans, speaker.as_ref(), &mut text);
+                speaker = annotation
+                    .split_once(char::is_whitespace)
+                    .map(|(_, name)| name.trim().to_string())
+                    .filter(|name| !name.is_empty());
+            } else if tag == "/v" {
+                push_voice_span(&mut spans, speaker.as_ref(), &mut text);
+                speaker = None;
+            } else if keep_tags {
+                text.push_str(&remaining[..remaining.len() - rest.len()]);
+            }
+            remaining = rest;
+        } else {
+            text.push(c);
+            remaining = &remaining[c.len_utf8()..];
+        }
+    }
+    push_voice_span(&mut spans, speaker.as_ref(), &mut text);
+
+    spans
+}
@@ -1,27 +1,38 @@
-use std::{borrow::Cow, fmt::Display, fs::File, io::BufReader, path::Path, str::FromStr};
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    io::{BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
 
-use buildstructor::Builder;
+use buildstructor::{buildstructor, Builder};
 use encoding_rs::Encoding;
-use encoding_rs_io::DecodeReaderBytesBuilder;
 
 use crate::{
-    encoding::detect_file_encoding,
+    encoding::{parse_from_reader, read_file_with_encoding},
     errors::Error,
+    microdvd::convert::mdvd_to_vtt_formatting,
     plain::PlainSubtitle,
-    subrip::convert::srt_to_vtt_formatting,
+    subrip::convert::{srt_coordinates_to_vtt_settings, srt_to_vtt_formatting},
     substation::{
-        ass::convert::ass_to_vtt_formatting, common::convert::split_formatting_tags,
+        ass::convert::ass_to_vtt_formatting,
+        common::convert::{reopen_tags_across_lines, split_formatting_tags},
         ssa::convert::ssa_to_vtt_formatting,
     },
-    traits::TimedSubtitle,
-    AssSubtitle, Moment, SsaSubtitle, SubRipSubtitle, Subtitle, TextEvent, TextEventInterface,
-    TextSubtitle, TimedEvent, TimedEventInterface, TimedMicroDvdSubtitle, TimedSubtitleFile,
+    traits::{RetainEvents, TimedSubtitle},
+    AssSubtitle, IdentifiableEvent, Moment, SsaSubtitle, SubRipSubtitle, Subtitle, TextEvent,
+    TextEventInterface, TextSubtitle, TimedEvent, TimedEventInterface, TimedMicroDvdSubtitle,
+    TimedSubtitleFile,
 };
 
-use super::{convert::strip_html_tags, parse::parse_vtt};
+use super::{
+    convert::{strip_html_tags, voice_spans},
+    parse::{cue_setting, find_malformed_timestamp, parse_vtt},
+};
 
 /// WebVTT (.vtt) subtitle data
-#[derive(Clone, Debug, Builder)]
+#[derive(Clone, Debug)]
 pub struct WebVttSubtitle {
     /// Header
     header: Option<String>,
@@ -31,10 +42,27 @@ pub struct WebVttSubtitle {
     styles: Vec<String>,
     /// List of regions (strings)
     regions: Vec<String>,
+    /// List of comments from NOTE blocks (strings)
+    notes: Vec<String>,
+    /// Blocks in the order they were given in the parsed file
+    blocks: Vec<WebVttBlock>,
+}
+
+/// Block of a WebVTT file, referring to the block's index in the list of blocks of the same kind
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WebVttBlock {
+    /// Cue, with its index in the list of cues
+    Cue(usize),
+    /// NOTE block, with its index in [`WebVttSubtitle::notes`]
+    Note(usize),
+    /// STYLE block, with its index in [`WebVttSubtitle::styles`]
+    Style(usize),
+    /// REGION block, with its index in [`WebVttSubtitle::regions`]
+    Region(usize),
 }
 
 /// WebVTT subtitle cue (event)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Builder)]
 pub struct WebVttCue {
     /// WebVTT identifier
     pub identifier: Option<String>,
@@ -48,7 +76,102 @@ pub struct WebVttCue {
     pub end: Moment,
 }
 
+/// Known settings of a WebVTT cue, such as `align:start position:10%`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WebVttCueSettings {
+    /// Writing direction (`rl` or `lr`)
+    pub vertical: Option<String>,
+    /// Line position, as a line number or percentage, with optional alignment (e.g. `0` or `90%,end`)
+    pub line: Option<String>,
+    /// Position of the cue box as a percentage, with optional alignment (e.g. `10%,line-left`)
+    pub position: Option<String>,
+    /// Size of the cue box as a percentage
+    pub size: Option<String>,
+    /// Text alignment (`start`, `center`, `end`, `left` or `right`)
+    pub align: Option<String>,
+    /// Identifier of the region the cue belongs to
+    pub region: Option<String>,
+}
+
+#[buildstructor]
 impl WebVttSubtitle {
+    /// Create a subtitle from its parts, for use by [`WebVttSubtitle::builder`] so that the
+    /// block order is not part of the builder
+    #[builder(visibility = "pub")]
+    fn new(
+        header: Option<String>,
+        cues: Vec<WebVttCue>,
+        styles: Vec<String>,
+        regions: Vec<String>,
+        notes: Vec<String>,
+    ) -> Self {
+        Self {
+            header,
+            cues,
+            styles,
+            regions,
+            notes,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Set the order of the blocks in the parsed file
+    pub(crate) fn with_blocks(mut self, blocks: Vec<WebVttBlock>) -> Self {
+        self.blocks = blocks;
+        self
+    }
+
+    /// Get the order of the blocks in the parsed file.
+    ///
+    /// The order is kept up to date when blocks are removed, and when cues are removed or sorted.
+    /// Blocks which were added afterwards are not included.
+    #[must_use]
+    pub fn blocks(&self) -> &[WebVttBlock] {
+        self.blocks.as_slice()
+    }
+
+    /// Remove the given block from the order of blocks, shifting the indices of later blocks of the same kind
+    fn remove_block(&mut self, removed: WebVttBlock) {
+        self.blocks.retain(|block| *block != removed);
+        for block in &mut self.blocks {
+            match (block, removed) {
+                (WebVttBlock::Cue(index), WebVttBlock::Cue(removed))
+                | (WebVttBlock::Note(index), WebVttBlock::Note(removed))
+                | (WebVttBlock::Style(index), WebVttBlock::Style(removed))
+                | (WebVttBlock::Region(index), WebVttBlock::Region(removed))
+                    if *index > removed =>
+                {
+                    *index -= 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reorder cues, keeping only those at the given indices, in the given order
+    fn reorder_cues(&mut self, order: &[usize]) {
+        let mut new_indices = vec![None; self.cues.len()];
+        for (new, old) in order.iter().enumerate() {
+            new_indices[*old] = Some(new);
+        }
+        self.blocks.retain_mut(|block| match block {
+            WebVttBlock::Cue(index) => match new_indices.get(*index).copied().flatten() {
+                Some(new) => {
+                    *index = new;
+                    true
+                }
+                None => false,
+            },
+            _ => true,
+        });
+
+        let mut old: Vec<Option<WebVttCue>> = self.cues.drain(..).map(Some).collect();
+        self.cues = order
+            .iter()
+            .filter_map(|index| old[*index].take())
+            .collect();
+    }
+
     /// Get header
     #[must_use]
     pub fn header(&self) -> Option<&String> {
@@ -82,6 +205,35 @@ impl WebVttSubtitle {
         self.styles.get_mut(index)
     }
 
+    /// Add a style (CSS) block to the end of the list of styles
+    pub fn add_style(&mut self, css: impl Into<String>) {
+        self.styles.push(css.into());
+    }
+
+    /// Remove style at index, returning it if it exists
+    pub fn remove_style(&mut self, index: usize) -> Option<String> {
+        (index < self.styles.len()).then(|| {
+            self.remove_block(WebVttBlock::Style(index));
+            self.styles.remove(index)
+        })
+    }
+
+    /// Add a cue to the end of the list of cues
+    pub fn push_cue(&mut self, cue: WebVttCue) {
+        self.cues.push(cue);
+    }
+
+    /// Get list of comments from NOTE blocks as a slice
+    #[must_use]
+    pub fn notes(&self) -> &[String] {
+        self.notes.as_slice()
+    }
+
+    /// Get list of comments from NOTE blocks as a mutable slice
+    pub fn notes_mut(&mut self) -> &mut [String] {
+        self.notes.as_mut_slice()
+    }
+
     /// Get list of regions as a slice
     #[must_use]
     pub fn regions(&self) -> &[String] {
@@ -104,17 +256,81 @@ impl WebVttSubtitle {
         self.regions.get_mut(index)
     }
 
-    fn open_file_with_encoding(
-        path: &Path,
-        encoding: Option<&'static Encoding>,
-    ) -> Result<Self, Error> {
-        let file = File::open(path)?;
-        let transcoded = DecodeReaderBytesBuilder::new()
-            .encoding(encoding)
-            .build(file);
-        let reader = BufReader::new(transcoded);
+    /// Add a region definition to the end of the list of regions
+    pub fn add_region(&mut self, region: impl Into<String>) {
+        self.regions.push(region.into());
+    }
 
-        Ok(parse_vtt(reader))
+    /// Remove region at index, returning it if it exists
+    pub fn remove_region(&mut self, index: usize) -> Option<String> {
+        (index < self.regions.len()).then(|| {
+            self.remove_block(WebVttBlock::Region(index));
+            self.regions.remove(index)
+        })
+    }
+
+    /// Append the `STYLE` and `REGION` blocks of another subtitle to this one,
+    /// e.g. to combine a file containing only cues with a separate file containing only styles.
+    ///
+    /// Blocks which are identical to one already present are not added again.
+    /// Regions with the same `id` as a region already present replace it.
+    pub fn merge_styles_from(&mut self, other: &WebVttSubtitle) {
+        for style in &other.styles {
+            if !self.styles.contains(style) {
+                self.styles.push(style.clone());
+            }
+        }
+        for region in &other.regions {
+            let id = cue_setting(region, "id");
+            let existing = self.regions.iter().position(|existing| {
+                existing == region || id.is_some() && cue_setting(existing, "id") == id
+            });
+
+            match existing {
+                Some(index) => self.regions[index].clone_from(region),
+                None => self.regions.push(region.clone()),
+            }
+        }
+    }
+
+    /// Get indices of cues whose `region:` setting refers to a region which is not defined
+    /// in any of the subtitle's `REGION` blocks.
+    #[must_use]
+    pub fn undefined_region_references(&self) -> Vec<usize> {
+        let defined: Vec<&str> = self
+            .regions
+            .iter()
+            .filter_map(|region| cue_setting(region, "id"))
+            .collect();
+
+        self.cues
+            .iter()
+            .enumerate()
+            .filter(|(_, cue)| {
+                cue.settings
+                    .as_deref()
+                    .and_then(|settings| cue_setting(settings, "region"))
+                    .is_some_and(|id| !defined.contains(&id))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Parse WebVTT subtitle from a string, rejecting cue timestamps which do not strictly follow the WebVTT specification.
+    ///
+    /// Unlike [`FromStr::from_str`], timestamps such as `1:2:3.4` are not accepted.
+    /// Minutes and seconds must be two digits, milliseconds must be three digits,
+    /// and hours, if present, must be at least two digits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] identifying the first line with a malformed timestamp.
+    pub fn from_str_strict(s: &str) -> Result<Self, Error> {
+        if let Some((line, message)) = find_malformed_timestamp(s) {
+            return Err(Error::ParseError { line, message });
+        }
+
+        Self::from_str(s)
     }
 }
 
@@ -125,15 +341,14 @@ impl Subtitle for WebVttSubtitle {
         path: impl AsRef<Path>,
         encoding: Option<&'static Encoding>,
     ) -> Result<Self, Error> {
-        let mut enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), Some(30)).ok());
-        let mut result = Self::open_file_with_encoding(path.as_ref(), enc);
+        let text = read_file_with_encoding(path.as_ref(), encoding, Some(30))?;
+        let reader = BufReader::new(text.as_bytes());
 
-        if encoding.is_none() && result.is_err() {
-            enc = encoding.or_else(|| detect_file_encoding(path.as_ref(), None).ok());
-            result = Self::open_file_with_encoding(path.as_ref(), enc);
-        }
+        Ok(parse_vtt(reader))
+    }
 
-        result
+    fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        parse_from_reader(reader, |reader| parse_vtt(BufReader::new(reader)))
     }
 
     fn events(&self) -> &[WebVttCue] {
@@ -145,34 +360,135 @@ impl Subtitle for WebVttSubtitle {
     }
 }
 
+impl RetainEvents for WebVttSubtitle {
+    fn retain_events(&mut self, mut f: impl FnMut(&Self::Event) -> bool) {
+        let kept: Vec<usize> = (0..self.cues.len())
+            .filter(|index| f(&self.cues[*index]))
+            .collect();
+        self.reorder_cues(&kept);
+    }
+}
+
 impl TextSubtitle for WebVttSubtitle {}
 
-impl TimedSubtitle for WebVttSubtitle {}
+impl TimedSubtitle for WebVttSubtitle {
+    /// Get a transcript of the subtitle, as a list of lines of plaintext, each with its speaker
+    /// (if known), start time and end time.
+    ///
+    /// Cues containing multiple voice spans (`<v Name>`) are split into one line per voice span,
+    /// each with the timing of the cue. As with other formats, text is trimmed and empty lines are skipped.
+    fn to_dialogue_lines(&self) -> Vec<(Option<String>, Moment, Moment, String)> {
+        self.cues
+            .iter()
+            .flat_map(|cue| {
+                cue.voices()
+                    .into_iter()
+                    .map(|(speaker, text)| (speaker, cue.start, cue.end, text))
+            })
+            .collect()
+    }
+
+    /// Sort cues in chronological order, by start time and then by end time.
+    ///
+    /// Notes stay before the same cue when written.
+    fn sort_by_time(&mut self) {
+        let mut order: Vec<usize> = (0..self.cues.len()).collect();
+        order.sort_by_key(|index| (self.cues[*index].start, self.cues[*index].end));
+        self.reorder_cues(&order);
+    }
+}
+
+/// Blocks to write before the cues, notes to write before each cue, and notes to write after all cues
+type BlockLayout = (Vec<WebVttBlock>, Vec<Vec<usize>>, Vec<usize>);
+
+impl WebVttSubtitle {
+    /// Arrange blocks for writing, keeping notes in their original position relative to cues.
+    ///
+    /// Blocks given before the first cue keep their order, and are followed by any other styles and
+    /// regions, as those must be before the first cue. Notes between cues are written before the cue
+    /// which followed them, and notes which are not in the order of blocks are written last.
+    fn block_layout(&self) -> BlockLayout {
+        let first_cue = self
+            .blocks
+            .iter()
+            .position(|block| matches!(block, WebVttBlock::Cue(_)))
+            .unwrap_or(self.blocks.len());
+        let (leading, rest) = self.blocks.split_at(first_cue);
+
+        let mut header_blocks = leading.to_vec();
+        header_blocks.extend(
+            (0..self.styles.len())
+                .map(WebVttBlock::Style)
+                .chain((0..self.regions.len()).map(WebVttBlock::Region))
+                .filter(|block| !leading.contains(block)),
+        );
+
+        let mut before_cue = vec![Vec::new(); self.cues.len()];
+        let mut pending = Vec::new();
+        for block in rest {
+            match block {
+                WebVttBlock::Note(index) => pending.push(*index),
+                WebVttBlock::Cue(index) => {
+                    if let Some(notes) = before_cue.get_mut(*index) {
+                        notes.append(&mut pending);
+                    }
+                }
+                WebVttBlock::Style(_) | WebVttBlock::Region(_) => {}
+            }
+        }
+        pending.extend(
+            (0..self.notes.len()).filter(|index| !self.blocks.contains(&WebVttBlock::Note(*index))),
+        );
+
+        (header_blocks, before_cue, pending)
+    }
+}
+
+fn write_note(f: &mut std::fmt::Formatter<'_>, note: &str) -> std::fmt::Result {
+    writeln!(f)?;
+    if note.is_empty() {
+        writeln!(f, "NOTE")
+    } else if note.contains('\n') {
+        writeln!(f, "NOTE\n{note}")
+    } else {
+        writeln!(f, "NOTE {note}")
+    }
+}
 
 impl Display for WebVttSubtitle {
+    /// Styles and regions are always written before any cues, as required by the WebVTT specification.
+    /// Notes are written in their original position relative to cues.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "WEBVTT")?;
-        if let Some(header) = &self.header {
-            writeln!(f, " - {header}")?;
+        if let Some(header) = self.header.as_deref().filter(|h| !h.trim().is_empty()) {
+            write!(f, " - {header}")?;
         }
-        if !self.styles.is_empty() {
-            writeln!(f)?;
-            for style in &self.styles {
-                writeln!(f, "STYLE\n{style}")?;
+        writeln!(f)?;
+
+        let (header_blocks, before_cue, trailing_notes) = self.block_layout();
+        for block in header_blocks {
+            match block {
+                WebVttBlock::Note(index) => write_note(f, &self.notes[index])?,
+                WebVttBlock::Style(index) => {
+                    writeln!(f)?;
+                    writeln!(f, "STYLE\n{}", self.styles[index])?;
+                }
+                WebVttBlock::Region(index) => {
+                    writeln!(f)?;
+                    writeln!(f, "REGION\n{}", self.regions[index])?;
+                }
+                WebVttBlock::Cue(_) => {}
             }
         }
-        if !self.regions.is_empty() {
-            writeln!(f)?;
-            for region in &self.regions {
-                writeln!(f, "REGION\n{region}")?;
+        for (line, notes) in self.cues.iter().zip(before_cue) {
+            for index in notes {
+                write_note(f, &self.notes[index])?;
             }
-        }
-        if !self.cues.is_empty() {
             writeln!(f)?;
-            for line in &self.cues {
-                writeln!(f)?;
-                writeln!(f, "{line}")?;
-            }
+            writeln!(f, "{line}")?;
+        }
+        for index in trailing_notes {
+            write_note(f, &self.notes[index])?;
         }
 
         Ok(())
@@ -205,7 +521,7 @@ impl From<&AssSubtitle> for WebVttSubtitle {
                     let mut text = dialogue.text.replace("\\N", "\n");
                     if let Ok((_, separated)) = split_formatting_tags(text.as_str()) {
                         if let Ok((_, converted)) = ass_to_vtt_formatting(separated.as_str()) {
-                            text = converted;
+                            text = reopen_tags_across_lines(converted.as_str());
                         }
                     }
 
@@ -221,11 +537,17 @@ impl From<&AssSubtitle> for WebVttSubtitle {
             header: value.script_info().title.clone(),
             styles: Vec::new(),
             regions: Vec::new(),
+            notes: Vec::new(),
+            blocks: Vec::new(),
         }
     }
 }
 
 impl From<&TimedMicroDvdSubtitle> for WebVttSubtitle {
+    /// Convert MicroDVD (.sub) subtitles to .vtt format
+    ///
+    /// Colour codes (`{c:$bbggrr}`) are removed rather than converted to `<font color>` tags, as
+    /// those are not part of WebVTT and are not displayed by players.
     fn from(value: &TimedMicroDvdSubtitle) -> Self {
         Self {
             cues: value
@@ -233,7 +555,7 @@ impl From<&TimedMicroDvdSubtitle> for WebVttSubtitle {
                 .iter()
                 .map(|line| WebVttCue {
                     identifier: None,
-                    text: line.text.replace('|', "\n"),
+                    text: mdvd_to_vtt_formatting(line.text.as_str()),
                     settings: None,
                     start: line.start,
                     end: line.end,
@@ -242,6 +564,8 @@ impl From<&TimedMicroDvdSubtitle> for WebVttSubtitle {
             header: None,
             styles: Vec::new(),
             regions: Vec::new(),
+            notes: Vec::new(),
+            blocks: Vec::new(),
         }
     }
 }
@@ -256,7 +580,7 @@ impl From<&SsaSubtitle> for WebVttSubtitle {
                     let mut text = dialogue.text.replace("\\N", "\n");
                     if let Ok((_, separated)) = split_formatting_tags(text.as_str()) {
                         if let Ok((_, converted)) = ssa_to_vtt_formatting(separated.as_str()) {
-                            text = converted;
+                            text = reopen_tags_across_lines(converted.as_str());
                         }
                     }
                     WebVttCue {
@@ -271,6 +595,8 @@ impl From<&SsaSubtitle> for WebVttSubtitle {
             header: None,
             styles: Vec::new(),
             regions: Vec::new(),
+            notes: Vec::new(),
+            blocks: Vec::new(),
         }
     }
 }
@@ -283,6 +609,9 @@ impl From<&SubRipSubtitle> for WebVttSubtitle {
     /// The supported tags are `<b>`, `<i>`, and `<u>`. Any other tags will be stripped from the text.
     ///
     /// Bracket tags (of the form `{b}`) will be converted to HTML tags (`<b>`).
+    ///
+    /// Coordinates are converted to `position` and `line` cue settings. Since SubRip subtitles carry
+    /// no resolution information, coordinates are assumed to be relative to a 1280x720 canvas.
     fn from(value: &SubRipSubtitle) -> Self {
         Self {
             cues: value
@@ -295,7 +624,9 @@ impl From<&SubRipSubtitle> for WebVttSubtitle {
                     WebVttCue {
                         identifier: Some(line.line_number.to_string()),
                         text,
-                        settings: None,
+                        settings: line
+                            .parsed_coordinates()
+                            .map(|coordinates| srt_coordinates_to_vtt_settings(&coordinates)),
                         start: line.start,
                         end: line.end,
                     }
@@ -304,6 +635,8 @@ impl From<&SubRipSubtitle> for WebVttSubtitle {
             header: None,
             styles: Vec::new(),
             regions: Vec::new(),
+            notes: Vec::new(),
+            blocks: Vec::new(),
         }
     }
 }
@@ -361,10 +694,60 @@ impl From<PlainSubtitle> for WebVttSubtitle {
             header: None,
             styles: Vec::new(),
             regions: Vec::new(),
+            notes: Vec::new(),
+            blocks: Vec::new(),
         }
     }
 }
 
+impl WebVttCue {
+    /// Parse the known settings of the cue. Unknown settings are ignored.
+    #[must_use]
+    pub fn parsed_settings(&self) -> WebVttCueSettings {
+        let setting = |name: &str| {
+            self.settings
+                .as_deref()
+                .and_then(|settings| cue_setting(settings, name))
+                .map(std::string::ToString::to_string)
+        };
+
+        WebVttCueSettings {
+            vertical: setting("vertical"),
+            line: setting("line"),
+            position: setting("position"),
+            size: setting("size"),
+            align: setting("align"),
+            region: setting("region"),
+        }
+    }
+
+    /// Split the text of the cue by voice spans (`<v Name>` or `<v.class Name>`), returning each
+    /// speaker's name along with the text they speak, with any formatting tags removed.
+    ///
+    /// A voice span lasts until a closing `</v>` tag, the next voice span, or the end of the cue.
+    /// Text outside of any voice span has no speaker.
+    #[must_use]
+    pub fn voices(&self) -> Vec<(Option<String>, String)> {
+        voice_spans(self.text.as_str(), false)
+    }
+
+    /// Replace the settings of the cue with the given settings
+    pub fn set_settings(&mut self, settings: &WebVttCueSettings) {
+        let settings = settings.to_string();
+        self.settings = if settings.is_empty() {
+            None
+        } else {
+            Some(settings)
+        };
+    }
+}
+
+impl IdentifiableEvent for WebVttCue {
+    fn identifier(&self) -> Option<String> {
+        self.identifier.clone()
+    }
+}
+
 impl TextEvent for WebVttCue {
     fn unformatted_text(&self) -> Cow<'_, String> {
         let Ok((_, stripped)) = strip_html_tags(self.text.as_str()) else {
@@ -373,6 +756,17 @@ impl TextEvent for WebVttCue {
 
         Cow::Owned(stripped)
     }
+
+    /// Name given by the voice spans (`<v Name>`) of the cue, if the whole cue is spoken by a single voice.
+    /// Returns `None` if the cue has no voice spans, or contains several voices or text outside of a voice span.
+    fn speaker(&self) -> Option<String> {
+        let mut speakers = self.voices().into_iter().map(|(speaker, _)| speaker);
+        let first = speakers.next()??;
+
+        speakers
+            .all(|speaker| speaker.as_ref() == Some(&first))
+            .then_some(first)
+    }
 }
 
 impl TimedEvent for WebVttCue {}
@@ -405,15 +799,38 @@ impl TimedEventInterface for WebVttCue {
     }
 }
 
+impl Display for WebVttCueSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let settings = [
+            ("vertical", &self.vertical),
+            ("line", &self.line),
+            ("position", &self.position),
+            ("size", &self.size),
+            ("align", &self.align),
+            ("region", &self.region),
+        ];
+        let formatted: Vec<String> = settings
+            .iter()
+            .filter_map(|(name, value)| value.as_ref().map(|value| format!("{name}:{value}")))
+            .collect();
+
+        write!(f, "{}", formatted.join(" "))
+    }
+}
+
 impl Display for WebVttCue {
+    /// Cues with empty text are written without a payload, which is valid WebVTT and is read back as a cue
+    /// with empty text. Some players reject such cues, so remove them beforehand if necessary,
+    /// e.g. using [`RetainEvents::retain_events`](crate::RetainEvents::retain_events).
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}{}{} --> {}{}\n{}",
+            "{}{}{} --> {}{}{}\n{}",
             self.identifier.clone().unwrap_or_default(),
             if self.identifier.is_some() { "\n" } else { "" },
             self.start.as_vtt_timestamp(),
             self.end.as_vtt_timestamp(),
+            if self.settings.is_some() { " " } else { "" },
             self.settings.as_deref().unwrap_or_default(),
             self.text,
         )
@@ -2,4 +2,4 @@ pub(crate) mod convert;
 mod data;
 pub(crate) mod parse;
 
-pub use data::{WebVttCue, WebVttSubtitle};
+pub use data::{WebVttBlock, WebVttCue, WebVttCueSettings, WebVttSubtitle};
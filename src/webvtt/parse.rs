@@ -1,21 +1,24 @@
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufReader, Read};
 
 use nom::{
     branch::alt,
-    bytes::complete::{tag, tag_no_case, take_until},
-    character::complete::{char, i64, line_ending, multispace0, space0, space1},
-    combinator::{map, opt, rest, value},
+    bytes::complete::{tag, tag_no_case, take_until, take_while_m_n},
+    character::complete::{char, digit1, i64, line_ending, multispace0, one_of, space0, space1},
+    combinator::{all_consuming, map, map_res, opt, rest, value, verify},
     multi::separated_list1,
     sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     IResult, Parser,
 };
 
-use crate::{parsing::take_until_end_of_block, Moment, WebVttSubtitle};
+use crate::{
+    parsing::{lines, take_until_end_of_block},
+    Moment, WebVttSubtitle,
+};
 
-use super::WebVttCue;
+use super::{WebVttBlock, WebVttCue};
 
 #[derive(Debug)]
-enum WebVttBlock<'a> {
+enum ParsedBlock<'a> {
     Cue(WebVttCue),
     Note(String),
     Style(String),
@@ -31,13 +34,23 @@ pub(crate) fn parse_header(input: &str) -> IResult<&str, Option<&str>> {
     .parse(input)
 }
 
-fn parse_timestamp(input: &str) -> IResult<&str, Moment> {
+/// Get value of a setting from a cue settings string (e.g. `align:left line:0`)
+pub(crate) fn cue_setting<'a>(settings: &'a str, name: &str) -> Option<&'a str> {
+    settings.split_whitespace().find_map(|setting| {
+        setting
+            .split_once(':')
+            .filter(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    })
+}
+
+pub(crate) fn parse_timestamp(input: &str) -> IResult<&str, Moment> {
     alt((
         map(
             tuple((
                 delimited(space0, i64, char(':')),
                 terminated(i64, char(':')),
-                terminated(i64, char('.')),
+                terminated(i64, one_of(".,")),
                 terminated(i64, space0),
             )),
             |(h, m, s, ms)| Moment::from_timestamp(h, m, s, ms),
@@ -45,7 +58,7 @@ fn parse_timestamp(input: &str) -> IResult<&str, Moment> {
         map(
             tuple((
                 delimited(space0, i64, char(':')),
-                terminated(i64, char('.')),
+                terminated(i64, one_of(".,")),
                 terminated(i64, space0),
             )),
             |(m, s, ms)| Moment::from_timestamp(0, m, s, ms),
@@ -54,6 +67,77 @@ fn parse_timestamp(input: &str) -> IResult<&str, Moment> {
     .parse(input)
 }
 
+fn parse_strict_minutes(input: &str) -> IResult<&str, i64> {
+    terminated(
+        verify(
+            map_res(
+                take_while_m_n(2, 2, |c: char| c.is_ascii_digit()),
+                str::parse,
+            ),
+            |m| *m < 60,
+        ),
+        char(':'),
+    )
+    .parse(input)
+}
+
+fn parse_strict_seconds(input: &str) -> IResult<&str, (i64, i64)> {
+    separated_pair(
+        verify(
+            map_res(
+                take_while_m_n(2, 2, |c: char| c.is_ascii_digit()),
+                str::parse,
+            ),
+            |s| *s < 60,
+        ),
+        char('.'),
+        map_res(
+            take_while_m_n(3, 3, |c: char| c.is_ascii_digit()),
+            str::parse,
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_timestamp_strict(input: &str) -> IResult<&str, Moment> {
+    alt((
+        map(
+            tuple((
+                terminated(
+                    map_res(verify(digit1, |h: &str| h.len() >= 2), str::parse),
+                    char(':'),
+                ),
+                parse_strict_minutes,
+                parse_strict_seconds,
+            )),
+            |(h, m, (s, ms))| Moment::from_timestamp(h, m, s, ms),
+        ),
+        map(
+            pair(parse_strict_minutes, parse_strict_seconds),
+            |(m, (s, ms))| Moment::from_timestamp(0, m, s, ms),
+        ),
+    ))
+    .parse(input)
+}
+
+/// Check that every cue timing line uses timestamps of the exact form required by the WebVTT specification,
+/// i.e. `mm:ss.ttt` or `hh:mm:ss.ttt` with at least two digits for hours.
+///
+/// Returns the line number of the first offending timing line, starting from 1, alongside a description of the problem.
+pub(crate) fn find_malformed_timestamp(input: &str) -> Option<(usize, String)> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| Some((index + 1, line.split_once("-->")?)))
+        .find_map(|(number, (start, rest))| {
+            let end = rest.split_whitespace().next().unwrap_or_default();
+            [start.trim(), end]
+                .into_iter()
+                .find(|timestamp| all_consuming(parse_timestamp_strict)(timestamp).is_err())
+                .map(|timestamp| (number, format!("malformed timestamp {timestamp:?}")))
+        })
+}
+
 fn parse_cue_timing(input: &str) -> IResult<&str, ((Moment, Moment), Option<&str>)> {
     terminated(
         tuple((
@@ -69,7 +153,7 @@ fn parse_cue_identifier(input: &str) -> IResult<&str, &str> {
     terminated(take_until("\n"), line_ending).parse(input)
 }
 
-fn parse_cue(input: &str) -> IResult<&str, WebVttBlock> {
+fn parse_cue(input: &str) -> IResult<&str, ParsedBlock> {
     map(
         tuple((
             alt((
@@ -79,10 +163,12 @@ fn parse_cue(input: &str) -> IResult<&str, WebVttBlock> {
             take_until_end_of_block,
         )),
         |((identifier, ((start, end), settings)), text)| {
-            WebVttBlock::Cue(WebVttCue {
+            ParsedBlock::Cue(WebVttCue {
                 identifier: identifier.map(std::string::ToString::to_string),
                 text,
-                settings: settings.map(std::string::ToString::to_string),
+                settings: settings
+                    .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .filter(|s| !s.is_empty()),
                 start,
                 end,
             })
@@ -91,38 +177,38 @@ fn parse_cue(input: &str) -> IResult<&str, WebVttBlock> {
     .parse(input)
 }
 
-fn parse_style(input: &str) -> IResult<&str, WebVttBlock> {
+fn parse_style(input: &str) -> IResult<&str, ParsedBlock> {
     map(
         preceded(pair(tag("STYLE"), line_ending), take_until_end_of_block),
-        WebVttBlock::Style,
+        ParsedBlock::Style,
     )
     .parse(input)
 }
 
-fn parse_note(input: &str) -> IResult<&str, WebVttBlock> {
+fn parse_note(input: &str) -> IResult<&str, ParsedBlock> {
     map(
         preceded(
             pair(tag("NOTE"), alt((space1, line_ending))),
             take_until_end_of_block,
         ),
-        WebVttBlock::Note,
+        ParsedBlock::Note,
     )
     .parse(input)
 }
 
-fn parse_region(input: &str) -> IResult<&str, WebVttBlock> {
+fn parse_region(input: &str) -> IResult<&str, ParsedBlock> {
     map(
         preceded(pair(tag("REGION"), line_ending), take_until_end_of_block),
-        WebVttBlock::Region,
+        ParsedBlock::Region,
     )
     .parse(input)
 }
 
-fn parse_invalid(input: &str) -> IResult<&str, WebVttBlock> {
-    map(alt((take_until("\n\n"), rest)), WebVttBlock::Invalid).parse(input)
+fn parse_invalid(input: &str) -> IResult<&str, ParsedBlock> {
+    map(alt((take_until("\n\n"), rest)), ParsedBlock::Invalid).parse(input)
 }
 
-fn parse_block(input: &str) -> IResult<&str, WebVttBlock> {
+fn parse_block(input: &str) -> IResult<&str, ParsedBlock> {
     preceded(
         multispace0,
         alt((
@@ -136,7 +222,7 @@ fn parse_block(input: &str) -> IResult<&str, WebVttBlock> {
     .parse(input)
 }
 
-fn parse_blocks(input: &str) -> IResult<&str, Vec<WebVttBlock>> {
+fn parse_blocks(input: &str) -> IResult<&str, Vec<ParsedBlock>> {
     separated_list1(parse_double_newline, parse_block).parse(input)
 }
 
@@ -145,7 +231,7 @@ fn parse_double_newline(input: &str) -> IResult<&str, &str> {
 }
 
 pub(crate) fn parse_vtt<T: Read>(reader: BufReader<T>) -> WebVttSubtitle {
-    let mut lines = reader.lines();
+    let mut lines = lines(reader);
 
     let mut header = None;
     if let Some(Ok(first_line)) = lines.next() {
@@ -158,6 +244,8 @@ pub(crate) fn parse_vtt<T: Read>(reader: BufReader<T>) -> WebVttSubtitle {
     let mut cues = Vec::new();
     let mut styles = Vec::new();
     let mut regions = Vec::new();
+    let mut notes = Vec::new();
+    let mut block_order = Vec::new();
     let mut is_streaming = true;
     while is_streaming {
         if let Some(has_line) = lines.next() {
@@ -179,10 +267,23 @@ pub(crate) fn parse_vtt<T: Read>(reader: BufReader<T>) -> WebVttSubtitle {
         };
         for block in blocks {
             match block {
-                WebVttBlock::Cue(cue) => cues.push(cue),
-                WebVttBlock::Style(style) => styles.push(style),
-                WebVttBlock::Region(region) => regions.push(region),
-                _ => {}
+                ParsedBlock::Cue(cue) => {
+                    block_order.push(WebVttBlock::Cue(cues.len()));
+                    cues.push(cue);
+                }
+                ParsedBlock::Style(style) => {
+                    block_order.push(WebVttBlock::Style(styles.len()));
+                    styles.push(style);
+                }
+                ParsedBlock::Region(region) => {
+                    block_order.push(WebVttBlock::Region(regions.len()));
+                    regions.push(region);
+                }
+                ParsedBlock::Note(note) => {
+                    block_order.push(WebVttBlock::Note(notes.len()));
+                    notes.push(note);
+                }
+                ParsedBlock::Invalid(_) => {}
             }
         }
 
@@ -194,5 +295,7 @@ pub(crate) fn parse_vtt<T: Read>(reader: BufReader<T>) -> WebVttSubtitle {
         .cues(cues)
         .styles(styles)
         .regions(regions)
+        .notes(notes)
         .build()
+        .with_blocks(block_order)
 }
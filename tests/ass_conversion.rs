@@ -1,6 +1,9 @@
 use std::str::FromStr;
 
-use aspasia::{AssSubtitle, SubRipSubtitle, Subtitle, WebVttSubtitle};
+use aspasia::{
+    substation::ass::CommentPolicy, AssSubtitle, SubRipSubtitle, Subtitle, TryConvert,
+    WebVttSubtitle,
+};
 
 #[test]
 
@@ -40,3 +43,97 @@ Dialogue: 0,0:00:27.92,0:00:30.26,*Default,NTP,0000,0000,0000,,- Oh\\N{\\b1\\i1}
     assert_eq!(vtt.events().len(), 1);
     assert_eq!(vtt.event(0).unwrap().text, "- Oh\n<b><i>- That's right")
 }
+
+#[test]
+fn formatting_carried_across_line_breaks() {
+    let ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Actor, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,{\\i1}a\\Nb{\\i0}
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,{\\b1\\i1}a\\N{\\i0}b{\\b0}
+Dialogue: 0,0:00:02.00,0:00:03.00,Default,,0,0,0,,a\\N{\\u1}b{\\u0}\\Nc
+",
+    )
+    .unwrap();
+    let srt = SubRipSubtitle::from(&ass);
+    let vtt = WebVttSubtitle::from(&ass);
+
+    assert_eq!(srt.event(0).unwrap().text, "<i>a</i>\n<i>b</i>");
+    assert_eq!(srt.event(1).unwrap().text, "<b><i>a</i></b>\n<b>b</b>");
+    assert_eq!(srt.event(2).unwrap().text, "a\n<u>b</u>\nc");
+    assert_eq!(vtt.event(0).unwrap().text, "<i>a</i>\n<i>b</i>");
+}
+
+#[test]
+fn try_convert_to_srt() {
+    let plain = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Actor, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,,,0,0,0,,Text
+",
+    )
+    .unwrap();
+    let styled = AssSubtitle::from_str(
+        "[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+Style: Sign,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,8,10,10,10,1
+
+[Fonts]
+fontname: chaucer.ttf
+!!!!
+
+[Events]
+Format: Layer, Start, End, Style, Actor, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Sign,,0,0,0,,Text
+Dialogue: 0,0:00:02.00,0:00:03.00,Default,,0,0,0,,Text
+Dialogue: 0,0:00:03.00,0:00:04.00,Undefined,,0,0,0,,Text
+",
+    )
+    .unwrap();
+
+    let converted: SubRipSubtitle = plain.try_convert().unwrap();
+    let loss = TryConvert::<SubRipSubtitle>::try_convert(&styled).unwrap_err();
+
+    assert_eq!(converted.events().len(), 1);
+    assert_eq!(loss.styles, ["Sign"]);
+    assert_eq!(loss.fonts, ["chaucer.ttf"]);
+    assert!(loss.graphics.is_empty());
+}
+
+#[test]
+fn include_comments_as_text() {
+    let ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Actor, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,First
+Comment: 0,0:00:02.00,0:00:03.00,Default,,0,0,0,,TL note: a pun
+Dialogue: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,Second
+",
+    )
+    .unwrap();
+    let texts = |policy: CommentPolicy| -> Vec<String> {
+        let mut ass = ass.clone();
+        ass.include_comments_as_text(policy);
+        SubRipSubtitle::from(&ass)
+            .events()
+            .iter()
+            .map(|event| event.text.clone())
+            .collect()
+    };
+
+    assert_eq!(texts(CommentPolicy::Drop), ["First", "Second"]);
+    assert_eq!(
+        texts(CommentPolicy::Plain),
+        ["First", "TL note: a pun", "Second"]
+    );
+    assert_eq!(
+        texts(CommentPolicy::Braces),
+        ["First", "{TL note: a pun}", "Second"]
+    );
+    assert_eq!(
+        texts(CommentPolicy::Brackets),
+        ["First", "[TL note: a pun]", "Second"]
+    );
+}
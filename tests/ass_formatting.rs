@@ -1,6 +1,9 @@
 use std::str::FromStr;
 
-use aspasia::{AssSubtitle, Subtitle, TextEvent, TextSubtitle};
+use aspasia::{
+    substation::ass::tags::{parse_override_block, AssKaraokeKind, AssTag},
+    AssSubtitle, Subtitle, TextEvent, TextSubtitle, TimedSubtitle,
+};
 
 const SUB_TEXT: &str = "[Script Info]
 
@@ -53,3 +56,147 @@ Dialogue: 0,0:00:14.80,0:00:19.90,*Default,NTP,0000,0000,0000,,Well, {\\p1}yes,
         "Well, no."
     );
 }
+
+#[test]
+fn event_margins_and_layer() {
+    let mut ass = AssSubtitle::from_str(SUB_TEXT).unwrap();
+    let event = ass.event_mut(0).unwrap();
+
+    event.set_margins(10, -5, 20);
+    event.set_layer(3);
+
+    assert_eq!(
+        (event.margin_l, event.margin_r, event.margin_v),
+        (10, 0, 20)
+    );
+    assert_eq!(event.layer, 3);
+    assert!(ass
+        .to_string()
+        .contains("Dialogue: 3,0:00:27.92,0:00:30.26,*Default,NTP,10,0,20,,"));
+
+    let event = ass.event_mut(0).unwrap();
+    event.reset_margins();
+
+    assert_eq!((event.margin_l, event.margin_r, event.margin_v), (0, 0, 0));
+}
+
+#[test]
+fn plaintext_cache() {
+    let mut ass = AssSubtitle::from_str(SUB_TEXT).unwrap();
+
+    assert_eq!(ass.cached_plaintext(0), None);
+
+    ass.build_plaintext_cache();
+
+    assert_eq!(
+        ass.cached_plaintext(0),
+        Some(ass.event(0).unwrap().as_plaintext().as_str())
+    );
+    assert_eq!(ass.cached_plaintext(1), None);
+
+    ass.event_mut(0).unwrap().text = "Changed".to_string();
+
+    assert_eq!(ass.cached_plaintext(0), None);
+
+    ass.build_plaintext_cache();
+    ass.sort_by_time();
+
+    assert_eq!(ass.cached_plaintext(0), None);
+}
+
+#[test]
+fn parse_override_tags() {
+    assert_eq!(
+        parse_override_block("{\\b1\\i0\\fnArial\\fs20.5\\3c&H202020&\\alpha&H80&}"),
+        vec![
+            AssTag::Bold(1),
+            AssTag::Italic(false),
+            AssTag::FontName("Arial".to_string()),
+            AssTag::FontSize(20.5),
+            AssTag::Colour {
+                index: 3,
+                colour: "&H202020&".to_string()
+            },
+            AssTag::Alpha {
+                index: 0,
+                alpha: "&H80&".to_string()
+            },
+        ]
+    );
+    assert_eq!(
+        parse_override_block("\\pos(10,20)\\move(0,0,100,50,0,500)\\fad(200,300)\\shad1"),
+        vec![
+            AssTag::Position { x: 10.0, y: 20.0 },
+            AssTag::Move {
+                from: (0.0, 0.0),
+                to: (100.0, 50.0),
+                times: Some((0, 500)),
+            },
+            AssTag::Fade {
+                fade_in: 200,
+                fade_out: 300
+            },
+            AssTag::Other("shad1".to_string()),
+        ]
+    );
+    assert_eq!(
+        parse_override_block("{\\t(0,1000,\\fs30\\clip(0,0,10,10))\\kf50}"),
+        vec![
+            AssTag::Transform {
+                times: Some((0, 1000)),
+                accel: None,
+                tags: vec![
+                    AssTag::FontSize(30.0),
+                    AssTag::Other("clip(0,0,10,10)".to_string())
+                ],
+            },
+            AssTag::Karaoke {
+                kind: AssKaraokeKind::Fill,
+                duration: 500.into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn event_tag_spans() {
+    let ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Actor, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:03.00,Default,,0,0,0,,{\\k20}Ka{\\k30\\b1}ra{\\k50}oke{\\r}",
+    )
+    .unwrap();
+    let spans = ass.event(0).unwrap().tags();
+
+    let text: Vec<&str> = spans.iter().map(|span| span.text.as_str()).collect();
+    assert_eq!(text, ["Ka", "ra", "oke", ""]);
+    assert_eq!(spans[1].tags[1], AssTag::Bold(1));
+    assert_eq!(spans[3].tags, [AssTag::Reset(None)]);
+
+    let total: i64 = spans
+        .iter()
+        .flat_map(|span| &span.tags)
+        .filter_map(|tag| match tag {
+            AssTag::Karaoke { duration, .. } => Some(i64::from(*duration)),
+            _ => None,
+        })
+        .sum();
+    assert_eq!(total, 1000);
+}
+
+#[test]
+fn right_to_left_marks_on_each_line() {
+    let mut ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,{\\an8}مرحبا\\Nשלום",
+    )
+    .unwrap();
+
+    ass.mark_rtl();
+
+    assert_eq!(
+        ass.event(0).unwrap().text,
+        "\u{200F}{\\an8}مرحبا\\N\u{200F}שלום"
+    );
+}
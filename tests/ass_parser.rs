@@ -1,6 +1,12 @@
 use std::str::FromStr;
 
-use aspasia::{AssSubtitle, Subtitle};
+use aspasia::{
+    substation::{
+        ass::{AssEvent, AssScriptInfo, AssStyle},
+        SubStationEventKind,
+    },
+    AssSubtitle, Error, Moment, RetainEvents, Subtitle, TimeDelta, TimedSubtitle,
+};
 
 #[test]
 fn dialogue() {
@@ -31,6 +37,19 @@ Command: 0,0:00:01.00,0:00:03.40,Default,,0,0,0,,ls
     assert_eq!(ass.command(0).unwrap().text, "ls");
 }
 
+#[test]
+fn carriage_return_line_endings() {
+    let ass = AssSubtitle::from_str(
+        "[Script Info]\rTitle: Old Mac\r\r[Events]\rFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\rDialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,First\rDialogue: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,Second\r",
+    )
+    .unwrap();
+
+    assert_eq!(ass.script_info().title.as_deref(), Some("Old Mac"));
+    assert_eq!(ass.events().len(), 2);
+    assert_eq!(ass.event(0).unwrap().text, "First");
+    assert_eq!(ass.event(1).unwrap().text, "Second");
+}
+
 #[test]
 fn ignore_comments_and_unrecognised_lines() {
     let ass = AssSubtitle::from_str(
@@ -52,3 +71,678 @@ Dialogue: 0,0:00:03.20,0:00:05.40,Default,,0,0,0,,Mark.
     assert_eq!(ass.event(0).unwrap().text, "Hi,");
     assert_eq!(ass.event(1).unwrap().text, "Mark.");
 }
+
+#[test]
+fn comment_events() {
+    let ass = AssSubtitle::from_str(
+        "[Script Info]
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:03.00,Default,,0,0,0,,Hi,
+Comment: 0,0:00:02.00,0:00:05.00,Default,,0,0,0,,TL note: a greeting
+",
+    )
+    .unwrap();
+
+    assert_eq!(ass.events().len(), 1);
+    assert_eq!(ass.comments().len(), 1);
+    assert_eq!(ass.comment(0).unwrap().kind, SubStationEventKind::Comment);
+    assert_eq!(ass.comment(0).unwrap().text, "TL note: a greeting");
+
+    let reparsed = AssSubtitle::from_str(ass.to_string().as_str()).unwrap();
+    assert_eq!(reparsed.comments(), ass.comments());
+    assert!(ass
+        .to_string()
+        .contains("Comment: 0,0:00:02.00,0:00:05.00,Default,,0,0,0,,TL note: a greeting"));
+}
+
+#[test]
+fn comment_positions() {
+    let mut ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:05.00,0:00:06.00,Default,,0,0,0,,First
+Comment: 0,0:00:00.00,0:00:00.00,Default,,0,0,0,,Note on second
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Second
+Dialogue: 0,0:00:07.00,0:00:08.00,Default,,0,0,0,,Third
+",
+    )
+    .unwrap();
+    let texts = |ass: &AssSubtitle| -> Vec<String> {
+        ass.to_string()
+            .lines()
+            .filter_map(|line| line.rsplit_once(",,").map(|(_, text)| text.to_string()))
+            .collect()
+    };
+
+    assert_eq!(texts(&ass), ["First", "Note on second", "Second", "Third"]);
+
+    ass.sort_by_time();
+    assert_eq!(texts(&ass), ["Note on second", "Second", "First", "Third"]);
+
+    ass.retain_events(|event| event.text != "Second");
+    assert_eq!(texts(&ass), ["Note on second", "First", "Third"]);
+}
+
+#[test]
+fn embedded_fonts_roundtrip() {
+    let ass = AssSubtitle::from_str(
+        "[Script Info]
+ScriptType: v4.00+
+
+[Fonts]
+fontname: chaucer.ttf
+!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!
+
+[Graphics]
+filename: logo.bmp
+!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,Text
+",
+    )
+    .unwrap();
+
+    let reparsed = AssSubtitle::from_str(ass.to_string().as_str()).unwrap();
+
+    assert_eq!(reparsed.fonts().len(), 1);
+    assert_eq!(reparsed.fonts()[0].fontname, "chaucer.ttf");
+    assert_eq!(reparsed.fonts()[0].data, ass.fonts()[0].data);
+    assert_eq!(reparsed.graphics().len(), 1);
+    assert_eq!(reparsed.graphics()[0].filename, "logo.bmp");
+    assert_eq!(reparsed.events().len(), 1);
+}
+
+#[test]
+fn fractional_second_digits() {
+    let ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.5,0:00:03.50,Default,,0,0,0,,One
+Dialogue: 0,0:00:04.05,0:00:05.123,Default,,0,0,0,,Two",
+    )
+    .unwrap();
+
+    assert_eq!(ass.event(0).unwrap().start, 1500.into());
+    assert_eq!(ass.event(0).unwrap().end, 3500.into());
+    assert_eq!(ass.event(1).unwrap().start, 4050.into());
+    assert_eq!(ass.event(1).unwrap().end, 5123.into());
+}
+
+fn default_style(name: &str) -> AssStyle {
+    AssStyle {
+        name: name.to_string(),
+        fontname: "Arial".to_string(),
+        fontsize: 20,
+        primary_colour: "&H00FFFFFF".to_string(),
+        secondary_colour: "&H000000FF".to_string(),
+        outline_colour: "&H00000000".to_string(),
+        back_colour: "&H00000000".to_string(),
+        bold: true,
+        italic: false,
+        underline: false,
+        strike_out: false,
+        scale_x: 100,
+        scale_y: 100,
+        spacing: 0,
+        angle: 0.0,
+        border_style: 1,
+        outline: 2,
+        shadow: 2,
+        alignment: 2,
+        margin_l: 10,
+        margin_r: 10,
+        margin_v: 10,
+        encoding: 1,
+    }
+}
+
+#[test]
+fn add_and_remove_style() {
+    let mut ass = AssSubtitle::from_str(
+        "[Script Info]
+ScriptType: v4.00+
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,Sign,,0,0,0,,Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Text",
+    )
+    .unwrap();
+
+    ass.add_style(default_style("Default"));
+    ass.add_style(default_style("Sign"));
+
+    let reparsed = AssSubtitle::from_str(ass.to_string().as_str()).unwrap();
+
+    assert_eq!(reparsed.styles().len(), 2);
+    assert_eq!(reparsed.styles()[0].name, "Default");
+    assert!(reparsed.styles()[0].bold);
+    assert!(!reparsed.styles()[0].italic);
+    assert_eq!(reparsed.styles()[1].name, "Sign");
+
+    ass.remove_style("Sign");
+
+    assert_eq!(ass.styles().len(), 1);
+    assert_eq!(ass.styles()[0].name, "Default");
+    assert_eq!(ass.event(0).unwrap().style, None);
+    assert_eq!(ass.event(1).unwrap().style.as_deref(), Some("Default"));
+}
+
+#[test]
+fn remove_style_matches_names_loosely() {
+    let mut ass = AssSubtitle::from_str(
+        "[Script Info]
+ScriptType: v4.00+
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,*Sign,,0,0,0,,Text
+Dialogue: 0,0:00:01.00,0:00:02.00,sign,,0,0,0,,Text
+Dialogue: 0,0:00:02.00,0:00:03.00,Default,,0,0,0,,Text",
+    )
+    .unwrap();
+
+    ass.add_style(default_style("Default"));
+    ass.add_style(default_style("sign"));
+    ass.remove_style("Sign");
+
+    assert_eq!(ass.styles().len(), 1);
+    assert_eq!(ass.styles()[0].name, "Default");
+    assert_eq!(ass.event(0).unwrap().style, None);
+    assert_eq!(ass.event(1).unwrap().style, None);
+    assert_eq!(ass.event(2).unwrap().style.as_deref(), Some("Default"));
+}
+
+#[test]
+fn shift_all_event_kinds() {
+    let mut ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Text
+Picture: 0,0:00:01.00,0:00:03.00,Default,,0,0,0,,logo.bmp",
+    )
+    .unwrap();
+
+    ass.shift(TimeDelta::from(1500));
+
+    assert_eq!(ass.event(0).unwrap().start, 2500.into());
+    assert_eq!(ass.picture(0).unwrap().start, 2500.into());
+    assert_eq!(ass.picture(0).unwrap().end, 4500.into());
+}
+
+#[test]
+fn split_all_event_kinds() {
+    let mut ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Before
+Comment: 0,0:00:06.00,0:00:07.00,Default,,0,0,0,,Note
+Dialogue: 0,0:00:06.00,0:00:07.00,Default,,0,0,0,,After
+Picture: 0,0:00:04.00,0:00:06.00,Default,,0,0,0,,logo.bmp
+Command: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,command",
+    )
+    .unwrap();
+
+    let tail = ass.split_at_rebased(Moment::from(5000));
+
+    assert_eq!(ass.events().len(), 1);
+    assert_eq!(ass.comments().len(), 0);
+    assert_eq!(ass.commands().len(), 1);
+    assert_eq!(ass.picture(0).unwrap().end, 5000.into());
+
+    assert_eq!(tail.events().len(), 1);
+    assert_eq!(tail.event(0).unwrap().start, 1000.into());
+    assert_eq!(tail.comments().len(), 1);
+    assert_eq!(tail.comments()[0].start, 1000.into());
+    assert_eq!(tail.commands().len(), 0);
+    assert_eq!(tail.picture(0).unwrap().start, 0.into());
+    assert_eq!(tail.picture(0).unwrap().end, 1000.into());
+}
+
+#[test]
+fn dialogue_without_margins() {
+    let ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:03.00,Default,,Text, with a comma
+Dialogue: 0,0:00:03.00,0:00:04.00,Default,,1,2,3,,Full",
+    )
+    .unwrap();
+
+    assert_eq!(ass.events().len(), 2);
+    assert_eq!(ass.event(0).unwrap().text, "Text, with a comma");
+    assert_eq!(ass.event(0).unwrap().margin_l, 0);
+    assert_eq!(ass.event(0).unwrap().start, 1000.into());
+    assert_eq!(ass.event(1).unwrap().text, "Full");
+    assert_eq!(ass.event(1).unwrap().margin_v, 3);
+}
+
+#[test]
+fn asterisk_style_names() {
+    let ass = AssSubtitle::from_str(
+        "[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+Style: *Sign,Arial,30,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,-1,0,0,0,100,100,0,0,1,2,2,8,10,10,10,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,*Default,,0,0,0,,Text",
+    )
+    .unwrap();
+
+    let reparsed = AssSubtitle::from_str(ass.to_string().as_str()).unwrap();
+    let event_style = reparsed.event(0).unwrap().style.as_deref().unwrap();
+
+    assert_eq!(reparsed.styles()[1].name, "*Sign");
+    assert_eq!(event_style, "*Default");
+    assert_eq!(reparsed.style_by_name(event_style).unwrap().name, "Default");
+    assert_eq!(reparsed.style_by_name("Sign").unwrap().fontsize, 30);
+    assert!(reparsed.style_by_name("Missing").is_none());
+}
+
+#[test]
+fn missing_script_info() {
+    let ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,First
+Dialogue: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,Second",
+    )
+    .unwrap();
+
+    assert_eq!(ass.events().len(), 2);
+    assert_eq!(ass.event(0).unwrap().text, "First");
+    assert_eq!(ass.event(1).unwrap().text, "Second");
+    assert_eq!(ass.script_info().script_type, None);
+}
+
+#[test]
+fn dedup_identical_styles() {
+    let styles = "[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+Style: Sign,Arial,30,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,-1,0,0,0,100,100,0,0,1,2,2,8,10,10,10,1";
+    let mut ass = AssSubtitle::from_str(
+        format!(
+            "{styles}
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,First"
+        )
+        .as_str(),
+    )
+    .unwrap();
+    let other = AssSubtitle::from_str(
+        "[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Main,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1",
+    )
+    .unwrap();
+
+    for style in other.styles() {
+        ass.add_style(style.clone());
+    }
+    ass.event_mut(0).unwrap().style = Some("*Main".to_string());
+    ass.dedup_styles();
+
+    let names: Vec<&str> = ass.styles().iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["Default", "Sign"]);
+    assert_eq!(ass.event(0).unwrap().style.as_deref(), Some("Default"));
+}
+
+#[test]
+fn from_mkv_parts() {
+    let codec_private = "[Script Info]
+ScriptType: v4.00+
+PlayResX: 1920
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text";
+    let ass = AssSubtitle::from_mkv_parts(
+        codec_private,
+        &[
+            (
+                Moment::from(3000),
+                Moment::from(4000),
+                "1,0,Default,,0,0,0,,Second, with a comma",
+            ),
+            (
+                Moment::from(1000),
+                Moment::from(2000),
+                "0,1,Default,Actor,10,20,30,,{\\i1}First",
+            ),
+        ],
+    );
+
+    assert_eq!(ass.script_info().play_res_x.as_deref(), Some("1920"));
+    assert_eq!(ass.styles().len(), 1);
+    assert_eq!(ass.events().len(), 2);
+
+    let first = ass.event(0).unwrap();
+    assert_eq!(first.text, "{\\i1}First");
+    assert_eq!(first.layer, 1);
+    assert_eq!(first.name.as_deref(), Some("Actor"));
+    assert_eq!(
+        (first.margin_l, first.margin_r, first.margin_v),
+        (10, 20, 30)
+    );
+    assert_eq!(first.start, Moment::from(1000));
+    assert_eq!(ass.event(1).unwrap().text, "Second, with a comma");
+    assert_eq!(ass.event(1).unwrap().end, Moment::from(4000));
+}
+
+#[test]
+fn format_with_text_not_last() {
+    let ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Text, Effect
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello, world
+",
+    )
+    .unwrap();
+
+    assert_eq!(ass.event(0).unwrap().text, "Hello, world");
+    assert!(ass.to_string().contains(
+        "Format: Layer, Start, End, Style, Actor, MarginL, MarginR, MarginV, Effect, Text\n"
+    ));
+}
+
+#[test]
+fn format_field_order_roundtrip() {
+    let input = "[Script Info]
+ScriptType: v4.00+
+
+[V4+ Styles]
+Format: Fontname, Name, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Arial,Default,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+
+[Events]
+Format: Start, End, Layer, Style, Name, Effect, MarginL, MarginR, MarginV, Text
+Dialogue: 0:00:01.00,0:00:02.00,1,Default,Bob,,0,0,0,Hello, world
+";
+    let ass = AssSubtitle::from_str(input).unwrap();
+
+    assert_eq!(ass.events_format_fields()[..3], ["Start", "End", "Layer"]);
+    assert_eq!(ass.styles_format_fields()[0], "Fontname");
+    assert_eq!(ass.styles()[0].name, "Default");
+    assert_eq!(ass.styles()[0].fontname, "Arial");
+
+    let event = ass.event(0).unwrap();
+    assert_eq!(event.layer, 1);
+    assert_eq!(event.start, Moment::from(1000));
+    assert_eq!(event.name.as_deref(), Some("Bob"));
+    assert_eq!(event.text, "Hello, world");
+
+    let output = ass.to_string();
+    assert!(output.contains(
+        "Format: Start, End, Layer, Style, Name, Effect, MarginL, MarginR, MarginV, Text\nDialogue: 0:00:01.00,0:00:02.00,1,Default,Bob,,0,0,0,Hello, world\n"
+    ));
+    assert!(output.contains("\nStyle: Arial,Default,20,"));
+
+    let reparsed = AssSubtitle::from_str(output.as_str()).unwrap();
+    assert_eq!(reparsed.events(), ass.events());
+    assert_eq!(reparsed.styles()[0].fontname, "Arial");
+}
+
+#[test]
+fn strict_parsing() {
+    let valid = "[Script Info]
+; Comment line
+Title: Strict
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Comment: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,Note
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello
+
+[Aegisub Project Garbage]
+Video File: video.mkv
+";
+    let ass = AssSubtitle::from_str_strict(valid).unwrap();
+    assert_eq!(ass.events().len(), 1);
+    assert_eq!(ass.script_info().title, Some("Strict".to_string()));
+
+    let garbage = "This is not a subtitle file\n";
+    assert!(AssSubtitle::from_str(garbage).unwrap().events().is_empty());
+    assert!(matches!(
+        AssSubtitle::from_str_strict(garbage),
+        Err(Error::ParseError { line: 1, .. })
+    ));
+
+    let truncated = "[Script Info]
+Title: Strict
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello
+Dialogue: 0,0:00:03.";
+    assert!(matches!(
+        AssSubtitle::from_str_strict(truncated),
+        Err(Error::ParseError { line: 7, .. })
+    ));
+
+    let malformed_comment = "[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Comment: 0,0:00:00.00
+";
+    assert!(matches!(
+        AssSubtitle::from_str_strict(malformed_comment),
+        Err(Error::ParseError { line: 3, .. })
+    ));
+}
+
+#[test]
+fn effective_style() {
+    let ass = AssSubtitle::from_str(
+        "[Script Info]
+ScriptType: v4.00+
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,20,&H80FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+Style: Sign,Arial,30,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,-1,-1,0,0,100,100,0,0,1,2,2,8,10,10,10,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,{\\b1\\fs32}{\\c&H0000FF&\\u1}Bold {\\i1}text
+Dialogue: 0,0:00:02.00,0:00:03.00,Sign,,0,0,0,,{\\b0\\fscx120}Not bold
+Dialogue: 0,0:00:03.00,0:00:04.00,Missing,,0,0,0,,Unknown style
+",
+    )
+    .unwrap();
+
+    let style = ass.effective_style(0).unwrap();
+    assert!(style.bold);
+    assert!(style.underline);
+    assert!(!style.italic);
+    assert_eq!(style.fontsize, 32);
+    assert_eq!(style.primary_colour, "&H800000FF");
+    assert!(!ass.style_by_name("Default").unwrap().bold);
+
+    let style = ass.effective_style(1).unwrap();
+    assert!(!style.bold);
+    assert!(style.italic);
+    assert_eq!(style.fontsize, 30);
+
+    assert_eq!(ass.effective_style(2).unwrap().name, "Default");
+    assert!(ass.effective_style(3).is_none());
+}
+
+#[test]
+fn all_event_kinds() {
+    let mut ass = AssSubtitle::from_str(
+        "[Script Info]
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Command: 0,0:00:04.00,0:00:05.00,Default,,0,0,0,,ls
+Sound: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,sound.wav
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello
+Picture: 0,0:00:02.00,0:00:03.00,Default,,0,0,0,,picture.bmp
+",
+    )
+    .unwrap();
+
+    let texts: Vec<_> = ass.all_events().map(|event| event.text.as_str()).collect();
+    assert_eq!(texts, vec!["Hello", "picture.bmp", "sound.wav", "ls"]);
+
+    for event in ass.all_events_mut() {
+        event.start += TimeDelta::from(1000);
+    }
+    assert_eq!(ass.event(0).unwrap().start, Moment::from(2000));
+    assert_eq!(ass.command(0).unwrap().start, Moment::from(5000));
+    assert_eq!(ass.command(0).unwrap().end, Moment::from(5000));
+}
+
+#[test]
+fn event_keyword_case_normalized() {
+    let ass = AssSubtitle::from_str(
+        "[Script Info]
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+dialogue :  0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Lowercase
+DIALOGUE: 0,0:00:02.00,0:00:03.00,Default,,0,0,0,,Uppercase
+",
+    )
+    .unwrap();
+    assert_eq!(ass.events().len(), 2);
+
+    let output = ass.to_string();
+    assert!(output.contains("Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Lowercase\n"));
+    assert!(output.contains("Dialogue: 0,0:00:02.00,0:00:03.00,Default,,0,0,0,,Uppercase\n"));
+    assert!(!output.contains("dialogue"));
+    assert!(!output.contains("DIALOGUE"));
+}
+
+#[test]
+fn style_lookup() {
+    let mut ass = AssSubtitle::from_str(
+        "[Script Info]
+ScriptType: v4.00+
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+Style: Sign,Arial,30,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,-1,0,0,0,100,100,0,0,1,2,2,8,10,10,10,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,sign,,0,0,0,,Lowercase style name
+Dialogue: 0,0:00:02.00,0:00:03.00,Missing,,0,0,0,,Unknown style
+Dialogue: 0,0:00:03.00,0:00:04.00,,,0,0,0,,No style
+",
+    )
+    .unwrap();
+
+    assert_eq!(ass.style_by_name("SIGN").unwrap().name, "Sign");
+    assert_eq!(ass.style_by_name("*default").unwrap().name, "Default");
+    assert!(ass.style_by_name("Missing").is_none());
+
+    ass.style_by_name_mut("sign").unwrap().fontsize = 40;
+    assert_eq!(ass.styles()[1].fontsize, 40);
+
+    let style_names: Vec<_> = ass
+        .events()
+        .iter()
+        .map(|event| ass.event_style(event).unwrap().name.as_str())
+        .collect();
+    assert_eq!(style_names, vec!["Sign", "Default", "Default"]);
+}
+
+#[test]
+fn dialogue_lines() {
+    let ass = AssSubtitle::from_str(
+        "[Script Info]
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:03.00,Default,Fred,0,0,0,,{\\i1}Hi,\\NBill
+Dialogue: 0,0:00:03.20,0:00:05.40,Default,,0,0,0,,Hello. 
+Dialogue: 0,0:00:06.00,0:00:07.00,Default,Fred,0,0,0,,{\\i1}
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        ass.to_dialogue_lines(),
+        vec![
+            (
+                Some("Fred".to_string()),
+                Moment::from(1000),
+                Moment::from(3000),
+                "Hi,\nBill".to_string()
+            ),
+            (
+                None,
+                Moment::from(3200),
+                Moment::from(5400),
+                "Hello.".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn unknown_script_info_keys() {
+    let ass = AssSubtitle::from_str(
+        "[Script Info]
+Title: Example
+ScaledBorderAndShadow: yes
+YCbCr Matrix: TV.709
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:03.00,Default,,0,0,0,,Hi,
+",
+    )
+    .unwrap();
+
+    assert_eq!(ass.script_info().title.as_deref(), Some("Example"));
+    assert_eq!(
+        ass.script_info().extra,
+        vec![
+            ("ScaledBorderAndShadow".to_string(), "yes".to_string()),
+            ("YCbCr Matrix".to_string(), "TV.709".to_string()),
+        ]
+    );
+
+    let output = ass.to_string();
+    assert!(output.contains("\nScaledBorderAndShadow: yes\nYCbCr Matrix: TV.709\n"));
+    let reparsed = AssSubtitle::from_str(output.as_str()).unwrap();
+    assert_eq!(reparsed.script_info().extra, ass.script_info().extra);
+}
+
+#[test]
+fn builder() {
+    let ass = AssSubtitle::builder()
+        .script_info(AssScriptInfo::builder().title("Built").build())
+        .dialogue(vec![AssEvent {
+            kind: SubStationEventKind::Dialogue,
+            layer: 0,
+            start: 1000.into(),
+            end: 2000.into(),
+            style: Some("Default".to_string()),
+            name: None,
+            margin_l: 0,
+            margin_r: 0,
+            margin_v: 0,
+            effect: None,
+            text: "Text".to_string(),
+        }])
+        .styles(vec![AssStyle::default()])
+        .build();
+
+    let reparsed = AssSubtitle::from_str(ass.to_string().as_str()).unwrap();
+
+    assert_eq!(reparsed.script_info().title.as_deref(), Some("Built"));
+    assert_eq!(reparsed.events().len(), 1);
+    assert_eq!(reparsed.event(0).unwrap().text, "Text");
+    assert_eq!(reparsed.styles().len(), 1);
+}
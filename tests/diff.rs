@@ -0,0 +1,56 @@
+use std::str::FromStr;
+
+use aspasia::{diff, SubRipSubtitle, SubtitleDiff};
+
+#[test]
+fn diff_subrip() {
+    let old = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+Unchanged
+
+2
+00:00:03,000 --> 00:00:04,000
+Retimed
+
+3
+00:00:05,000 --> 00:00:06,000
+Old text
+
+4
+00:00:07,000 --> 00:00:08,000
+Removed
+",
+    )
+    .unwrap();
+    let new = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+Unchanged
+
+2
+00:00:03,500 --> 00:00:04,500
+Retimed
+
+3
+00:00:05,000 --> 00:00:06,000
+New text
+
+4
+00:00:09,000 --> 00:00:10,000
+Added
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        diff(&old, &new),
+        vec![
+            SubtitleDiff::TimingChanged { old: 1, new: 1 },
+            SubtitleDiff::TextChanged { old: 2, new: 2 },
+            SubtitleDiff::Removed(3),
+            SubtitleDiff::Added(3),
+        ]
+    );
+    assert!(diff(&old, &old).is_empty());
+}
@@ -1,4 +1,10 @@
-use aspasia::{SubRipSubtitle, Subtitle, TimedSubtitleFile};
+use std::str::FromStr;
+
+use aspasia::{
+    AssSubtitle, Error, ExportOptions, Format, SsaSubtitle, SubRipSubtitle, Subtitle,
+    TimedMicroDvdSubtitle, TimedSubtitleFile, WebVttSubtitle,
+};
+use encoding_rs::{GBK, REPLACEMENT, SHIFT_JIS, UTF_16BE, UTF_16LE};
 
 #[test]
 fn gbk_srt() {
@@ -9,3 +15,251 @@ fn gbk_srt() {
     assert_eq!(srt.event(1).unwrap().text, "还有更多的中文");
     assert_eq!(srt.event(2).unwrap().text, "再见");
 }
+
+#[test]
+fn from_path_detecting_gbk() {
+    let (srt, encoding) = SubRipSubtitle::from_path_detecting("./tests/data/gbk.srt").unwrap();
+
+    assert_eq!(encoding, GBK);
+    assert_eq!(srt.events().len(), 3);
+    assert_eq!(srt.event(0).unwrap().text, "这是中文");
+}
+
+#[test]
+fn export_shift_jis() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+こんにちは
+",
+    )
+    .unwrap();
+    let path = std::env::temp_dir().join("aspasia_export_shift_jis.srt");
+
+    srt.export_with_encoding(&path, SHIFT_JIS).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let reloaded = SubRipSubtitle::from_path_with_encoding(&path, Some(SHIFT_JIS)).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(std::str::from_utf8(&bytes).is_err());
+    assert_eq!(reloaded.event(0).unwrap().text, "こんにちは");
+}
+
+#[test]
+fn export_unrepresentable_characters() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+مرحبا
+",
+    )
+    .unwrap();
+    let path = std::env::temp_dir().join("aspasia_export_unrepresentable.srt");
+
+    assert!(matches!(
+        srt.export_with_encoding(&path, SHIFT_JIS),
+        Err(Error::EncodingError("Shift_JIS"))
+    ));
+}
+
+#[test]
+fn export_utf16() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+こんにちは
+",
+    )
+    .unwrap();
+    let path = std::env::temp_dir().join("aspasia_export_utf16.srt");
+
+    srt.export_with_encoding(&path, UTF_16LE).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let reloaded = SubRipSubtitle::from_path(&path).unwrap();
+
+    assert_eq!(bytes[..4], [0xFF, 0xFE, b'1', 0]);
+    assert_eq!(reloaded.event(0).unwrap().text, "こんにちは");
+
+    srt.export_with_encoding(&path, UTF_16BE).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let reloaded = SubRipSubtitle::from_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(bytes[..4], [0xFE, 0xFF, 0, b'1']);
+    assert_eq!(reloaded.event(0).unwrap().text, "こんにちは");
+
+    assert!(matches!(
+        srt.export_with_encoding(&path, REPLACEMENT),
+        Err(Error::UnsupportedEncodingError("replacement"))
+    ));
+}
+
+#[test]
+fn export_lossy_utf16() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+こんにちは م
+",
+    )
+    .unwrap();
+    let path = std::env::temp_dir().join("aspasia_export_lossy_utf16.srt");
+
+    assert!(!srt.export_with_encoding_lossy(&path, UTF_16LE).unwrap());
+    let bytes = std::fs::read(&path).unwrap();
+    let reloaded = SubRipSubtitle::from_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(bytes[..2], [0xFF, 0xFE]);
+    assert_eq!(reloaded.event(0).unwrap().text, "こんにちは م");
+    assert!(matches!(
+        srt.export_with_encoding_lossy(&path, REPLACEMENT),
+        Err(Error::UnsupportedEncodingError("replacement"))
+    ));
+}
+
+#[test]
+fn export_lossy_shift_jis_ass() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+こんにちは م
+",
+    )
+    .unwrap();
+    let file = TimedSubtitleFile::Ass(AssSubtitle::from(&srt));
+    let path = std::env::temp_dir().join("aspasia_export_lossy.ass");
+
+    assert!(matches!(
+        file.export_with_encoding(&path, SHIFT_JIS),
+        Err(Error::EncodingError("Shift_JIS"))
+    ));
+    assert!(file.export_with_encoding_lossy(&path, SHIFT_JIS).unwrap());
+    let reloaded = AssSubtitle::from_path_with_encoding(&path, Some(SHIFT_JIS)).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(reloaded.event(0).unwrap().text, "こんにちは &#1605;");
+}
+
+#[test]
+fn from_path_reads_bom_file() {
+    let path = std::env::temp_dir().join("aspasia_from_path_bom.srt");
+    std::fs::write(
+        &path,
+        "\u{feff}1\n00:00:01,000 --> 00:00:02,000\nÀ bientôt\n",
+    )
+    .unwrap();
+
+    let srt = SubRipSubtitle::from_path(&path).unwrap();
+    let (file, format) = TimedSubtitleFile::new_with_format(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(srt.events().len(), 1);
+    assert_eq!(srt.event(0).unwrap().text, "À bientôt");
+    assert_eq!(format, Format::SubRip);
+    assert_eq!(SubRipSubtitle::from(file).events(), srt.events());
+}
+
+#[test]
+fn from_path_missing_file() {
+    let path = std::env::temp_dir().join("aspasia_does_not_exist.srt");
+
+    assert!(matches!(
+        SubRipSubtitle::from_path(&path),
+        Err(Error::FileIoError(_))
+    ));
+    assert!(matches!(
+        TimedSubtitleFile::new(&path),
+        Err(Error::FileIoError(_))
+    ));
+
+    let err = SubRipSubtitle::from_path(&path).unwrap_err();
+    let source = std::error::Error::source(&err).expect("i/o error should be exposed as source");
+    assert_eq!(
+        source
+            .downcast_ref::<std::io::Error>()
+            .map(std::io::Error::kind),
+        Some(std::io::ErrorKind::NotFound)
+    );
+}
+
+#[test]
+fn export_with_bom() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+Text
+",
+    )
+    .unwrap();
+    let files = [
+        ("ass", TimedSubtitleFile::Ass(AssSubtitle::from(&srt))),
+        (
+            "sub",
+            TimedSubtitleFile::MicroDvd(TimedMicroDvdSubtitle::from(&srt)),
+        ),
+        ("ssa", TimedSubtitleFile::Ssa(SsaSubtitle::from(&srt))),
+        ("srt", TimedSubtitleFile::SubRip(srt.clone())),
+        ("vtt", TimedSubtitleFile::WebVtt(WebVttSubtitle::from(&srt))),
+    ];
+    let options = ExportOptions {
+        write_bom: true,
+        ..ExportOptions::default()
+    };
+
+    for (extension, file) in files {
+        let path = std::env::temp_dir().join(format!("aspasia_export_bom.{extension}"));
+
+        file.export_with_options(&path, options).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let reloaded = TimedSubtitleFile::new(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(bytes.starts_with(b"\xEF\xBB\xBF"), "{extension}");
+        assert_eq!(
+            SubRipSubtitle::from(reloaded).event(0).unwrap().text,
+            "Text",
+            "{extension}"
+        );
+    }
+}
+
+#[test]
+fn from_reader() {
+    let text = "1\n00:00:01,000 --> 00:00:02,000\nStreamed ✓\n";
+
+    let srt = SubRipSubtitle::from_reader(text.as_bytes()).unwrap();
+    assert_eq!(srt.event(0).unwrap().text, "Streamed ✓");
+
+    let utf16: Vec<u8> = [0xFF, 0xFE]
+        .into_iter()
+        .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+        .collect();
+    let srt = SubRipSubtitle::from_reader(utf16.as_slice()).unwrap();
+    assert_eq!(srt.event(0).unwrap().text, "Streamed ✓");
+
+    let vtt =
+        WebVttSubtitle::from_reader("WEBVTT\n\n00:01.000 --> 00:02.000\nStreamed\n".as_bytes())
+            .unwrap();
+    assert_eq!(vtt.events().len(), 1);
+}
+
+#[test]
+fn from_reader_error() {
+    struct FailingReader;
+
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("connection reset"))
+        }
+    }
+
+    assert!(matches!(
+        SubRipSubtitle::from_reader(FailingReader),
+        Err(Error::FileIoError(_))
+    ));
+    assert!(matches!(
+        AssSubtitle::from_reader(FailingReader),
+        Err(Error::FileIoError(_))
+    ));
+}
@@ -1,4 +1,9 @@
-use aspasia::{detect_format_from_str, Format};
+use std::fs;
+
+use aspasia::{
+    detect_format, detect_format_by_extension, detect_format_from_str, Error, Format, Subtitle,
+    TimedSubtitleFile,
+};
 
 #[test]
 fn srt() {
@@ -47,3 +52,117 @@ ScriptType: v4.00 ",
 
     assert_eq!(detected, Format::Ssa);
 }
+
+#[test]
+fn load_with_detected_format() {
+    let (sub, format) = TimedSubtitleFile::new_with_format("./tests/data/gbk.srt").unwrap();
+
+    assert_eq!(format, Format::SubRip);
+    assert!(matches!(sub, TimedSubtitleFile::SubRip(_)));
+}
+
+#[test]
+fn parse_str_with_format() {
+    let text = "1
+00:00:01,000 --> 00:00:02,000
+Hello
+";
+    let sub = TimedSubtitleFile::parse_str(text, Format::SubRip).unwrap();
+    let TimedSubtitleFile::SubRip(srt) = sub else {
+        panic!("expected SubRip subtitle");
+    };
+    assert_eq!(srt.event(0).unwrap().text, "Hello");
+
+    let detected = detect_format_from_str(text).unwrap();
+    assert!(matches!(
+        TimedSubtitleFile::parse_str(text, detected),
+        Ok(TimedSubtitleFile::SubRip(_))
+    ));
+}
+
+#[test]
+fn sub_extension_checks_content() {
+    let dir = std::env::temp_dir();
+    let microdvd_path = dir.join("aspasia_detect_microdvd.sub");
+    let subviewer_path = dir.join("aspasia_detect_subviewer.sub");
+    fs::write(&microdvd_path, "{0}{120}Help|me\n").unwrap();
+    fs::write(
+        &subviewer_path,
+        "00:00:01.00,00:00:02.50\nTimestamp based\n",
+    )
+    .unwrap();
+
+    let microdvd = detect_format(&microdvd_path);
+    let subviewer = detect_format(&subviewer_path);
+
+    fs::remove_file(&microdvd_path).unwrap();
+    fs::remove_file(&subviewer_path).unwrap();
+
+    assert_eq!(microdvd.unwrap(), Format::MicroDvd);
+    assert!(matches!(subviewer, Err(Error::FormatUnknownError)));
+}
+
+#[test]
+fn substation_without_script_info() {
+    assert_eq!(
+        detect_format_from_str("[V4+ Styles]\nFormat: Name, Fontname").unwrap(),
+        Format::Ass
+    );
+    assert_eq!(
+        detect_format_from_str("[V4 Styles]\nFormat: Name, Fontname").unwrap(),
+        Format::Ssa
+    );
+    assert_eq!(
+        detect_format_from_str(
+            "[Events]\nFormat: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text"
+        )
+        .unwrap(),
+        Format::Ssa
+    );
+    assert_eq!(
+        detect_format_from_str(
+            "[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text"
+        )
+        .unwrap(),
+        Format::Ass
+    );
+}
+
+#[test]
+fn unsupported_formats() {
+    let ttml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<tt xmlns=\"http://www.w3.org/ns/ttml\">";
+    let sami = "<SAMI>
+<HEAD>
+<TITLE>Title</TITLE>";
+    let lrc = "[00:12.00]Line one
+[00:17.20]Line two";
+
+    for text in [ttml, sami, lrc] {
+        assert!(matches!(
+            detect_format_from_str(text),
+            Err(Error::FormatUnknownError)
+        ));
+    }
+}
+
+#[test]
+fn unknown_format_error() {
+    let path = std::env::temp_dir().join("aspasia_unknown_format.txt");
+    fs::write(&path, "Just some notes\nwith no timings\n").unwrap();
+
+    let detected = detect_format(&path);
+    let loaded = TimedSubtitleFile::new(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert!(matches!(detected, Err(Error::FormatUnknownError)));
+    assert!(matches!(loaded, Err(Error::FormatUnknownError)));
+    assert!(matches!(
+        detect_format_by_extension("subtitle.txt"),
+        Err(Error::FormatUnknownError)
+    ));
+    assert_eq!(
+        Error::FormatUnknownError.to_string(),
+        "could not detect subtitle format automatically"
+    );
+}
@@ -1,6 +1,9 @@
 use std::str::FromStr;
 
-use aspasia::{MicroDvdSubtitle, Moment, Subtitle, TextEvent, TimedMicroDvdSubtitle};
+use aspasia::{
+    microdvd::MicroDvdControl, timing::Frame, AssSubtitle, MicroDvdSubtitle, Moment,
+    SubRipSubtitle, Subtitle, TextEvent, TimedMicroDvdSubtitle, WebVttSubtitle,
+};
 
 const SUB_TEXT: &str = "{1}{450}One
 {460}{510}Two
@@ -54,6 +57,26 @@ fn framerate_modification() {
     assert_eq!(sub.event(0).unwrap().end, Moment::from(25000));
 }
 
+#[test]
+fn event_frames() {
+    let mut sub = TimedMicroDvdSubtitle::from_str(SUB_TEXT).unwrap();
+
+    assert_eq!(
+        sub.event_frames(1),
+        Some((Frame::from(460), Frame::from(510)))
+    );
+
+    sub.set_event_frames(1, Frame::from(480), Frame::from(720));
+
+    assert_eq!(sub.event(1).unwrap().start, Moment::from(20000));
+    assert_eq!(sub.event(1).unwrap().end, Moment::from(30000));
+    assert_eq!(
+        sub.event_frames(1),
+        Some((Frame::from(480), Frame::from(720)))
+    );
+    assert_eq!(sub.event_frames(2), None);
+}
+
 #[test]
 fn convert_newlines() {
     let sub = TimedMicroDvdSubtitle::from_str("{0}{9}Hello|there").unwrap();
@@ -80,3 +103,99 @@ fn ignore_invalid_lines() {
     assert_eq!(sub.events().len(), 2);
     assert_eq!(sub.event(1).unwrap().text, "more");
 }
+
+#[test]
+fn convert_colour_codes() {
+    let sub = TimedMicroDvdSubtitle::from_str(
+        "{0}{50}{c:$0000ff}Red|Plain\n{50}{100}{C:$00ff00}Green|Too\n",
+    )
+    .unwrap();
+
+    let srt = SubRipSubtitle::from(&sub);
+    let ass = AssSubtitle::from(&sub);
+    let vtt = WebVttSubtitle::from(&sub);
+
+    assert_eq!(
+        srt.event(0).unwrap().text,
+        "<font color=\"#ff0000\">Red</font>\nPlain"
+    );
+    assert_eq!(
+        srt.event(1).unwrap().text,
+        "<font color=\"#00ff00\">Green\nToo</font>"
+    );
+    assert_eq!(ass.event(0).unwrap().text, "{\\c&H0000FF&}Red{\\c}\\NPlain");
+    assert_eq!(vtt.event(0).unwrap().text, "Red\nPlain");
+}
+
+#[test]
+fn control_codes() {
+    let text = "{0}{24}{Y:i}{C:$0000FF}Italic red text
+{25}{50}{F:Arial}{S:20}{P:0,0}Positioned|{y:b}Bold line
+{51}{75}No codes
+";
+    let sub = MicroDvdSubtitle::from_str(text).unwrap();
+
+    let first = sub.event(0).unwrap();
+    assert_eq!(
+        first.controls,
+        vec![
+            MicroDvdControl::Style("i".to_string()),
+            MicroDvdControl::Colour("$0000FF".to_string()),
+        ]
+    );
+    assert_eq!(first.text, "Italic red text");
+
+    let second = sub.event(1).unwrap();
+    assert_eq!(
+        second.controls,
+        vec![
+            MicroDvdControl::Font("Arial".to_string()),
+            MicroDvdControl::Size("20".to_string()),
+            MicroDvdControl::Other('P', "0,0".to_string()),
+        ]
+    );
+    assert_eq!(second.text, "Positioned|{y:b}Bold line");
+    assert!(sub.event(2).unwrap().controls.is_empty());
+
+    assert_eq!(sub.to_string(), text);
+    assert_eq!(
+        MicroDvdSubtitle::from(&TimedMicroDvdSubtitle::from_raw(&sub, None)).to_string(),
+        text
+    );
+}
+
+#[test]
+fn declared_framerate() {
+    let text = "{1}{1}25.000
+{0}{25}One second
+{50}{100}Two seconds
+";
+    let raw = MicroDvdSubtitle::from_str(text).unwrap();
+    assert_eq!(raw.declared_framerate(), Some(25.0));
+    assert_eq!(raw.events().len(), 3);
+
+    let sub = TimedMicroDvdSubtitle::from_str(text).unwrap();
+    assert_eq!(sub.framerate(), 25.0);
+    assert_eq!(sub.events().len(), 2);
+    assert_eq!(sub.event(0).unwrap().text, "One second");
+    assert_eq!(sub.event(0).unwrap().end, Moment::from(1000));
+    assert_eq!(sub.event(1).unwrap().end, Moment::from(4000));
+
+    let explicit = TimedMicroDvdSubtitle::from_raw(&raw, Some(50.0));
+    assert_eq!(explicit.framerate(), 50.0);
+    assert_eq!(explicit.events().len(), 2);
+    assert_eq!(explicit.event(0).unwrap().end, Moment::from(500));
+
+    let undeclared = TimedMicroDvdSubtitle::from_str(SUB_TEXT).unwrap();
+    assert_eq!(undeclared.framerate(), 24.0);
+    assert!(!undeclared.declares_framerate());
+
+    assert!(sub.declares_framerate());
+    assert_eq!(
+        sub.to_string(),
+        "{1}{1}25\n{0}{25}One second\n{50}{100}Two seconds\n"
+    );
+    let converted = MicroDvdSubtitle::from(&sub);
+    assert_eq!(converted.declared_framerate(), Some(25.0));
+    assert_eq!(converted.events().len(), 3);
+}
@@ -0,0 +1,72 @@
+use std::str::FromStr;
+
+use aspasia::{
+    AssSubtitle, EllipsisStyle, PunctuationOptions, QuoteStyle, SubRipSubtitle, Subtitle,
+    TextSubtitle,
+};
+
+#[test]
+fn straight_to_curly() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+<font color=\"#ffffff\">\"Don't go,\" she said...</font>
+'Quoted' (\"aside\")
+",
+    )
+    .unwrap();
+
+    srt.normalize_punctuation(PunctuationOptions {
+        quotes: Some(QuoteStyle::Curly),
+        ellipsis: Some(EllipsisStyle::Single),
+    });
+
+    assert_eq!(
+        srt.event(0).unwrap().text,
+        "<font color=\"#ffffff\">“Don’t go,” she said…</font>\n‘Quoted’ (“aside”)"
+    );
+}
+
+#[test]
+fn curly_to_straight() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+<i>“Don’t go,” she said…</i>
+‘Quoted’
+",
+    )
+    .unwrap();
+
+    srt.normalize_punctuation(PunctuationOptions {
+        quotes: Some(QuoteStyle::Straight),
+        ellipsis: Some(EllipsisStyle::ThreeDots),
+    });
+
+    assert_eq!(
+        srt.event(0).unwrap().text,
+        "<i>\"Don't go,\" she said...</i>\n'Quoted'"
+    );
+}
+
+#[test]
+fn unchanged_punctuation() {
+    let mut ass = AssSubtitle::from_str(
+        "[Script Info]
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:03.40,Default,,0,0,0,,{\\i1}Wait...{\\i0}\\N\"Why?\" “Fine”
+",
+    )
+    .unwrap();
+
+    ass.normalize_punctuation(PunctuationOptions {
+        quotes: Some(QuoteStyle::Curly),
+        ellipsis: None,
+    });
+
+    assert_eq!(
+        ass.event(0).unwrap().text,
+        "{\\i1}Wait...{\\i0}\\N“Why?” “Fine”"
+    );
+}
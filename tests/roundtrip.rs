@@ -0,0 +1,47 @@
+#![cfg(feature = "testing")]
+
+use aspasia::{testing::assert_roundtrip, AssSubtitle, SubRipSubtitle, WebVttSubtitle};
+
+#[test]
+fn roundtrip_subrip() {
+    assert_roundtrip::<SubRipSubtitle>(
+        "1
+00:00:01,000 --> 00:00:02,500
+<i>Hello</i>
+world
+
+2
+00:00:03,000 --> 00:00:04,000 X1:100 X2:200 Y1:300 Y2:400
+Positioned
+",
+    );
+}
+
+#[test]
+fn roundtrip_webvtt() {
+    assert_roundtrip::<WebVttSubtitle>(
+        "WEBVTT
+
+intro
+00:00:01.000 --> 00:00:02.500 align:start
+Hello
+
+00:00:03.000 --> 00:00:04.000
+World
+",
+    );
+}
+
+#[test]
+fn roundtrip_ass() {
+    assert_roundtrip::<AssSubtitle>(
+        "[Script Info]
+ScriptType: v4.00+
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.50,Default,,0,0,0,,{\\b1}Hello\\Nworld
+Comment: 1,0:00:03.00,0:00:04.00,Default,Actor,10,10,10,,Note
+",
+    );
+}
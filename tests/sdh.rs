@@ -0,0 +1,67 @@
+use std::str::FromStr;
+
+use aspasia::{AssSubtitle, SubRipSubtitle, Subtitle, TextSubtitle};
+
+#[test]
+fn remove_sdh() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+[door slams]
+
+2
+00:00:02,000 --> 00:00:04,000
+JOHN: Who's there?
+- (laughs)
+
+3
+00:00:04,000 --> 00:00:06,000
+- MARY: It's me. [CHUCKLES]
+- Oh (sort of) hello (SIGHS)
+
+4
+00:00:06,000 --> 00:00:08,000
+<i>(music playing)</i>
+
+5
+00:00:08,000 --> 00:00:10,000
+Meet me at 10:30, Dr. Smith: now.
+
+6
+00:00:10,000 --> 00:00:12,000
+Wait.  Listen.
+[SIREN]  Run!
+",
+    )
+    .unwrap();
+
+    srt.remove_sdh();
+
+    assert_eq!(srt.events().len(), 4);
+    assert_eq!(srt.event(0).unwrap().text, "Who's there?");
+    assert_eq!(
+        srt.event(1).unwrap().text,
+        "- It's me.\n- Oh (sort of) hello"
+    );
+    assert_eq!(
+        srt.event(2).unwrap().text,
+        "Meet me at 10:30, Dr. Smith: now."
+    );
+    assert_eq!(srt.event(3).unwrap().text, "Wait.  Listen.\nRun!");
+}
+
+#[test]
+fn remove_sdh_substation() {
+    let mut ass = AssSubtitle::from_str(
+        "[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,{\\pos(10,20)}[THUNDER]\\NBOB: Run!
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,(gasps)",
+    )
+    .unwrap();
+
+    ass.remove_sdh();
+
+    assert_eq!(ass.events().len(), 1);
+    assert_eq!(ass.event(0).unwrap().text, "{\\pos(10,20)}Run!");
+}
@@ -18,3 +18,93 @@ Dialogue: Marked=0,0:01:31.50,0:01:37.00,Zombie,,0000,0000,0100,,{\\a2}Brains...
     assert_eq!(ssa.events().len(), 2);
     assert_eq!(ssa.styles().len(), 1);
 }
+
+#[test]
+fn embedded_fonts_roundtrip() {
+    let ssa = SsaSubtitle::from_str(
+        "[Script Info]
+ScriptType: v4.00
+
+[Fonts]
+fontname: chaucer.ttf
+!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!
+
+[Events]
+Format: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: Marked=0,0:00:00.00,0:00:01.00,Default,,0,0,0,,Text
+",
+    )
+    .unwrap();
+
+    let reparsed = SsaSubtitle::from_str(ssa.to_string().as_str()).unwrap();
+
+    assert_eq!(reparsed.fonts().len(), 1);
+    assert_eq!(reparsed.fonts()[0].fontname, "chaucer.ttf");
+    assert_eq!(reparsed.fonts()[0].data, ssa.fonts()[0].data);
+    assert_eq!(reparsed.events().len(), 1);
+}
+
+#[test]
+fn format_field_order_roundtrip() {
+    let ssa = SsaSubtitle::from_str(
+        "[Script Info]
+
+[Events]
+Format: Marked, Start, End, Style, Name, Effect, MarginL, MarginR, MarginV, Text
+Dialogue: Marked=0,0:01:19.00,0:01:26.50,Zombie,,Karaoke,0000,0000,0100,Graahhh...",
+    )
+    .unwrap();
+
+    assert_eq!(ssa.events_format_fields()[5], "Effect");
+    assert_eq!(ssa.event(0).unwrap().margin_v, 100);
+    assert_eq!(ssa.event(0).unwrap().effect.as_deref(), Some("Karaoke"));
+    assert!(ssa.to_string().contains(
+        "Format: Marked, Start, End, Style, Name, Effect, MarginL, MarginR, MarginV, Text\nDialogue: Marked=0,0:01:19.00,0:01:26.50,Zombie,,Karaoke,0,0,100,Graahhh...\n"
+    ));
+}
+
+#[test]
+fn all_event_kinds() {
+    let mut ssa = SsaSubtitle::from_str(
+        "[Script Info]
+[Events]
+Format: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Movie: Marked=0,0:00:03.00,0:00:04.00,Default,,0000,0000,0000,,movie.avi
+Dialogue: Marked=0,0:00:01.00,0:00:02.00,Default,,0000,0000,0000,,Hello
+",
+    )
+    .unwrap();
+
+    let texts: Vec<_> = ssa.all_events().map(|event| event.text.as_str()).collect();
+    assert_eq!(texts, vec!["Hello", "movie.avi"]);
+
+    for event in ssa.all_events_mut() {
+        event.text.push('!');
+    }
+    assert_eq!(ssa.event(0).unwrap().text, "Hello!");
+    assert_eq!(ssa.movies()[0].text, "movie.avi!");
+}
+
+#[test]
+fn style_lookup() {
+    let mut ssa = SsaSubtitle::from_str("[Script Info]
+
+[V4 Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, TertiaryColour, BackColour, Bold, Italic, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, AlphaLevel, Encoding
+Style: Zombie,Courier New,32,16777215,65535,65535,-2147483640,-1,0,1,3,0,2,0,0,40,0,0
+
+[Events]
+Format: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: Marked=0,0:01:19.00,0:01:26.50,zombie,,0000,0000,0000,,Graahhh...
+Dialogue: Marked=0,0:01:31.50,0:01:37.00,Missing,,0000,0000,0100,,Brains...").unwrap();
+
+    assert_eq!(ssa.style_by_name("ZOMBIE").unwrap().name, "Zombie");
+    ssa.style_by_name_mut("zombie").unwrap().fontsize = 40;
+    assert_eq!(ssa.styles()[0].fontsize, 40);
+
+    assert_eq!(
+        ssa.event_style(ssa.event(0).unwrap()).unwrap().name,
+        "Zombie"
+    );
+    assert!(ssa.event_style(ssa.event(1).unwrap()).is_none());
+}
@@ -0,0 +1,111 @@
+use std::str::FromStr;
+
+use aspasia::{AssSubtitle, SubRipSubtitle, TimeDelta, TimedSubtitle};
+
+#[test]
+fn reading_speed_stats() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:00,000 --> 00:00:02,000
+<i>Ten chars!</i>
+
+2
+00:00:02,000 --> 00:00:03,000
+Thirty characters in a second!
+
+3
+00:00:03,000 --> 00:00:03,000
+Zero duration
+",
+    )
+    .unwrap();
+
+    let stats = srt.reading_speed_stats(20.0);
+
+    assert!((stats.avg_cps - 17.5).abs() < f64::EPSILON);
+    assert!((stats.max_cps - 30.0).abs() < f64::EPSILON);
+    assert_eq!(stats.events_over, 1);
+}
+
+#[test]
+fn reading_speed_ignores_line_breaks() {
+    let ass = AssSubtitle::from_str(
+        "[Script Info]
+ScriptType: v4.00+
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,{\\b1}Line\\Nbreak",
+    )
+    .unwrap();
+
+    let stats = ass.reading_speed_stats(20.0);
+
+    assert!((stats.max_cps - 9.0).abs() < f64::EPSILON);
+    assert_eq!(stats.events_over, 0);
+}
+
+#[test]
+fn reading_speed_empty() {
+    let srt = SubRipSubtitle::from_str("").unwrap();
+
+    assert_eq!(srt.reading_speed_stats(20.0), Default::default());
+}
+
+#[test]
+fn subtitle_stats() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:03,000
+<i>Ten chars!</i>
+Longest line here
+
+2
+00:00:02,500 --> 00:00:04,000
+Overlap
+
+3
+00:00:05,000 --> 00:00:06,000
+End
+",
+    )
+    .unwrap();
+
+    let stats = srt.stats();
+
+    assert_eq!(stats.event_count, 3);
+    assert_eq!(stats.total_duration, TimeDelta::from(5000));
+    assert_eq!(stats.display_time, TimeDelta::from(4500));
+    assert_eq!(stats.longest_line, 17);
+    assert_eq!(stats.overlaps, 1);
+    assert!((stats.max_cps - 13.5).abs() < f64::EPSILON);
+}
+
+#[test]
+fn event_density() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:00,500 --> 00:00:02,500
+First
+
+2
+00:00:01,000 --> 00:00:02,000
+Second
+
+3
+00:00:05,000 --> 00:00:06,000
+Third
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        srt.event_density(TimeDelta::from(1000)),
+        vec![1, 2, 1, 0, 0, 1]
+    );
+    assert_eq!(srt.event_density(TimeDelta::from(0)), Vec::<usize>::new());
+    assert!(SubRipSubtitle::from_str("")
+        .unwrap()
+        .event_density(TimeDelta::from(1000))
+        .is_empty());
+}
@@ -1,6 +1,8 @@
-use std::str::FromStr;
+use std::{fs, str::FromStr};
 
-use aspasia::{AssSubtitle, SubRipSubtitle, Subtitle, WebVttSubtitle};
+use aspasia::{
+    convert_file, AssSubtitle, IdentifiableEvent, SubRipSubtitle, Subtitle, WebVttSubtitle,
+};
 
 #[test]
 fn to_vtt_formatting() {
@@ -37,3 +39,109 @@ fn to_ass_formatting() {
         "{\\b1}{\\i1}Some{\\i0}{\\u1} mixed{\\u0}{\\b0} formatting\\N{\\c&H0000ff&}and more"
     );
 }
+
+#[test]
+fn convert_file_to_vtt() {
+    let dir = std::env::temp_dir();
+    let input = dir.join("aspasia_convert_file_input.srt");
+    let output = dir.join("aspasia_convert_file_output.vtt");
+    fs::write(
+        &input,
+        "1
+00:00:00,000 --> 00:00:02,520
+<b>Hello</b> there
+",
+    )
+    .unwrap();
+
+    convert_file(&input, &output).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&output).unwrap(),
+        "WEBVTT
+
+1
+00:00:00.000 --> 00:00:02.520
+<b>Hello</b> there
+"
+    );
+
+    fs::remove_file(input).unwrap();
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn to_vtt_timestamps() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:00,000 --> 00:00:02,520
+Text
+",
+    )
+    .unwrap();
+    let ass = AssSubtitle::from(&srt);
+
+    let from_srt = WebVttSubtitle::from(&srt).to_string();
+    let from_ass = WebVttSubtitle::from(&ass).to_string();
+
+    assert!(from_srt.contains("\n00:00:00.000 --> 00:00:02.520\n"));
+    assert!(from_ass.contains("\n00:00:00.000 --> 00:00:02.520\n"));
+}
+
+fn identifiers<S>(subtitle: &S) -> Vec<Option<String>>
+where
+    S: Subtitle,
+    S::Event: IdentifiableEvent,
+{
+    subtitle
+        .events()
+        .iter()
+        .map(IdentifiableEvent::identifier)
+        .collect()
+}
+
+#[test]
+fn event_identifiers() {
+    let srt = SubRipSubtitle::from_str(
+        "5
+00:00:00,000 --> 00:00:01,000
+First
+
+6
+00:00:01,000 --> 00:00:02,000
+Second
+",
+    )
+    .unwrap();
+    let vtt = WebVttSubtitle::from(&srt);
+    let ass = AssSubtitle::from(&srt);
+
+    assert_eq!(
+        identifiers(&srt),
+        [Some("5".to_string()), Some("6".to_string())]
+    );
+    assert_eq!(identifiers(&vtt), identifiers(&srt));
+    assert_eq!(identifiers(&ass), [None, None]);
+}
+
+#[test]
+fn to_vtt_coordinates() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:00,000 --> 00:00:02,000 X1:320 X2:960 Y1:648 Y2:700
+Positioned
+
+2
+00:00:02,000 --> 00:00:04,000
+Unpositioned
+",
+    )
+    .unwrap();
+    let vtt = WebVttSubtitle::from(&srt);
+
+    assert_eq!(
+        vtt.event(0).unwrap().settings.as_deref(),
+        Some("position:50% line:90%")
+    );
+    assert_eq!(vtt.event(1).unwrap().settings, None);
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use aspasia::{SubRipSubtitle, Subtitle, TextSubtitle};
+use aspasia::{ExportOptions, LineEnding, SubRipSubtitle, Subtitle, TextSubtitle};
 
 #[test]
 fn strip_nested_tags() {
@@ -54,3 +54,145 @@ Some <>text<",
 
     assert_eq!(srt.event(0).unwrap().text, "Some text<");
 }
+
+#[test]
+fn remove_font_tags() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:00,000 --> 00:00:02,000
+<font color=\"#ff0000\"><b>Red</b></font> and {i}<FONT face=\"Arial\">plain</FONT>{/i}
+",
+    )
+    .unwrap();
+    srt.event_mut(0).unwrap().remove_tag("font");
+
+    assert_eq!(srt.event(0).unwrap().text, "<b>Red</b> and {i}plain{/i}");
+}
+
+#[test]
+fn export_renumbered() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:00,000 --> 00:00:01,000
+First
+
+2
+00:00:01,000 --> 00:00:02,000
+Second
+",
+    )
+    .unwrap();
+    let path = std::env::temp_dir().join("aspasia_export_renumbered.srt");
+
+    srt.export_renumbered(&path, 41).unwrap();
+    let exported = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(exported.starts_with("41\n00:00:00,000 --> 00:00:01,000\nFirst\n\n42\n"));
+    assert_eq!(srt.event(0).unwrap().line_number, 1);
+}
+
+#[test]
+fn export_crlf() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:00,000 --> 00:00:01,000
+First
+line
+",
+    )
+    .unwrap();
+    let path = std::env::temp_dir().join("aspasia_export_crlf.srt");
+
+    srt.export_with_options(
+        &path,
+        ExportOptions {
+            line_ending: LineEnding::Crlf,
+            ..ExportOptions::default()
+        },
+    )
+    .unwrap();
+    let exported = std::fs::read_to_string(&path).unwrap();
+    srt.export_with_options(&path, ExportOptions::default())
+        .unwrap();
+    let default = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        exported,
+        "1\r\n00:00:00,000 --> 00:00:01,000\r\nFirst\r\nline\r\n"
+    );
+    assert_eq!(default, srt.to_string());
+    assert_eq!(srt.event(0).unwrap().text, "First\nline");
+}
+
+#[test]
+fn right_to_left_marks() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:00,000 --> 00:00:01,000
+مرحبا
+
+2
+00:00:01,000 --> 00:00:02,000
+\u{200F}שלום
+
+3
+00:00:02,000 --> 00:00:03,000
+مرحبا
+\u{200F}שלום
+",
+    )
+    .unwrap();
+
+    srt.mark_rtl();
+
+    assert_eq!(srt.event(0).unwrap().text, "\u{200F}مرحبا");
+    assert_eq!(srt.event(1).unwrap().text, "\u{200F}שלום");
+    assert_eq!(srt.event(2).unwrap().text, "\u{200F}مرحبا\n\u{200F}שלום");
+
+    srt.strip_bidi_marks();
+
+    assert_eq!(srt.event(0).unwrap().text, "مرحبا");
+    assert_eq!(srt.event(1).unwrap().text, "שלום");
+}
+
+#[test]
+fn empty_event_roundtrip() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+First
+
+2
+00:00:03,000 --> 00:00:04,000
+Second
+",
+    )
+    .unwrap();
+    srt.event_mut(0).unwrap().text = String::new();
+
+    let output = srt.to_string();
+    assert_eq!(
+        output,
+        "1\n00:00:01,000 --> 00:00:02,000\n\n\n2\n00:00:03,000 --> 00:00:04,000\nSecond\n"
+    );
+
+    let reparsed = SubRipSubtitle::from_str(output.as_str()).unwrap();
+    assert_eq!(reparsed.events().len(), 2);
+    assert_eq!(reparsed.event(0).unwrap().text, "");
+    assert_eq!(reparsed.event(1).unwrap().text, "Second");
+}
+
+#[test]
+fn write_to_vec() {
+    let text = "1
+00:00:00,000 --> 00:00:01,000
+Written to memory
+";
+    let srt = SubRipSubtitle::from_str(text).unwrap();
+    let mut buffer = Vec::new();
+
+    srt.to_writer(&mut buffer).unwrap();
+    assert_eq!(String::from_utf8(buffer).unwrap(), text);
+}
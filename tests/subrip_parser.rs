@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use aspasia::{SubRipSubtitle, Subtitle};
+use aspasia::{subrip::SubRipCoordinates, Error, Moment, SubRipSubtitle, Subtitle, TimeDelta};
 
 const MISNUMBERED_SUB: &str = "10
 00:00:01,000 --> 00:00:02,500
@@ -68,6 +68,50 @@ Some text",
     assert_eq!(srt.events().len(), 1);
 }
 
+#[test]
+fn numeric_line_within_text() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,500
+Chapter
+5
+begins
+
+2
+00:00:03,000 --> 00:00:06,000
+Next
+",
+    )
+    .unwrap();
+
+    assert_eq!(srt.events().len(), 2);
+    assert_eq!(srt.event(0).unwrap().text, "Chapter\n5\nbegins");
+    assert_eq!(srt.event(1).unwrap().line_number, 2);
+}
+
+#[test]
+fn numeric_continuation() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,500
+It happened in
+
+1999
+
+2
+00:00:03,000 --> 00:00:06,000
+Countdown
+
+24
+",
+    )
+    .unwrap();
+
+    assert_eq!(srt.events().len(), 2);
+    assert_eq!(srt.event(0).unwrap().text, "It happened in\n\n1999");
+    assert_eq!(srt.event(1).unwrap().text, "Countdown\n\n24");
+}
+
 #[test]
 fn crlf() {
     let srt = SubRipSubtitle::from_str(
@@ -87,6 +131,31 @@ More text\r
     assert_eq!(srt.event(1).unwrap().text, "More text");
 }
 
+#[test]
+fn carriage_return_line_endings() {
+    let srt = SubRipSubtitle::from_str(
+        "1\r00:00:01,000 --> 00:00:02,500\rSome text\r\r2\r00:00:03,000 --> 00:00:06,000\rMore\rtext\r",
+    )
+    .unwrap();
+
+    assert_eq!(srt.events().len(), 2);
+    assert_eq!(srt.event(0).unwrap().text, "Some text");
+    assert_eq!(srt.event(1).unwrap().text, "More\ntext");
+}
+
+#[test]
+fn mixed_line_endings() {
+    let srt = SubRipSubtitle::from_str(
+        "1\r\n00:00:01,000 --> 00:00:02,500\nSome\r\ntext\r\n\n2\n00:00:03,000 --> 00:00:06,000\rMore text\r\r3\r\n00:00:07,000 --> 00:00:08,000\r\nLast\n",
+    )
+    .unwrap();
+
+    assert_eq!(srt.events().len(), 3);
+    assert_eq!(srt.event(0).unwrap().text, "Some\ntext");
+    assert_eq!(srt.event(1).unwrap().text, "More text");
+    assert_eq!(srt.event(2).unwrap().text, "Last");
+}
+
 #[test]
 fn misnumbered_lines() {
     let srt = SubRipSubtitle::from_str(MISNUMBERED_SUB).unwrap();
@@ -107,6 +176,47 @@ fn renumbering() {
     assert_eq!(srt.event(1).unwrap().line_number, 2);
 }
 
+#[test]
+fn numbering_detection() {
+    let mut srt = SubRipSubtitle::from_str(MISNUMBERED_SUB).unwrap();
+
+    assert!(!srt.has_sequential_numbering());
+    assert_eq!(srt.numbering_gaps(), vec![0, 1]);
+
+    srt.event_mut(0).unwrap().line_number = 1;
+    assert_eq!(srt.numbering_gaps(), vec![1]);
+
+    srt.renumber();
+    assert!(srt.has_sequential_numbering());
+    assert!(srt.numbering_gaps().is_empty());
+}
+
+#[test]
+fn merging() {
+    let mut first = SubRipSubtitle::from_str(MISNUMBERED_SUB).unwrap();
+    let second = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+Part two
+",
+    )
+    .unwrap();
+    first.merge(&second, TimeDelta::from(60_000));
+
+    assert_eq!(first.events().len(), 3);
+    assert_eq!(
+        first
+            .events()
+            .iter()
+            .map(|event| event.line_number)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(first.event(2).unwrap().text, "Part two");
+    assert_eq!(first.event(2).unwrap().start, Moment::from(61_000));
+    assert_eq!(second.event(0).unwrap().start, Moment::from(1000));
+}
+
 #[test]
 fn ending_continuation() {
     let srt = SubRipSubtitle::from_str(
@@ -134,3 +244,127 @@ exists
     assert_eq!(srt.event(0).unwrap().text, "Some\n\n\nstuff");
     assert_eq!(srt.event(1).unwrap().text, "Broken\n\nup\n\ntext\nexists");
 }
+
+#[test]
+fn truncated_mid_text() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+First
+
+2
+00:00:03,000 --> 00:00:04,000
+Second line is cu",
+    )
+    .unwrap();
+
+    assert_eq!(srt.events().len(), 2);
+    assert_eq!(srt.event(0).unwrap().text, "First");
+    assert_eq!(srt.event(1).unwrap().text, "Second line is cu");
+}
+
+#[test]
+fn truncated_mid_timing() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+First
+
+2
+00:00:03,000 --> 00:0",
+    )
+    .unwrap();
+
+    assert_eq!(srt.events().len(), 1);
+    assert_eq!(srt.event(0).unwrap().text, "First");
+
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+First
+
+2
+",
+    )
+    .unwrap();
+
+    assert_eq!(srt.events().len(), 1);
+    assert_eq!(srt.event(0).unwrap().text, "First");
+}
+
+#[test]
+fn coordinates() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,500 X1:100 X2:600 Y1:500 Y2:550
+Positioned
+
+2
+00:00:03,000 --> 00:00:06,000 X1:100 X2:abc
+Malformed
+
+3
+00:00:07,000 --> 00:00:08,000
+Unpositioned
+",
+    )
+    .unwrap();
+
+    let expected = SubRipCoordinates {
+        x1: 100,
+        x2: 600,
+        y1: 500,
+        y2: 550,
+    };
+    assert_eq!(srt.event(0).unwrap().parsed_coordinates(), Some(expected));
+    assert_eq!(srt.event(1).unwrap().parsed_coordinates(), None);
+    assert_eq!(srt.event(2).unwrap().parsed_coordinates(), None);
+
+    let event = srt.event_mut(2).unwrap();
+    event.set_coordinates(SubRipCoordinates { y1: 0, ..expected });
+    assert_eq!(
+        event.coordinates.as_deref(),
+        Some("X1:100 X2:600 Y1:0 Y2:550")
+    );
+    assert!(srt
+        .to_string()
+        .contains("00:00:07,000 --> 00:00:08,000 X1:100 X2:600 Y1:0 Y2:550\n"));
+}
+
+#[test]
+fn strict_parsing() {
+    let valid =
+        "1\n00:00:01,000 --> 00:00:02,000\nHello\n\n2\n00:00:03,000 --> 00:00:04,000\nWorld\n";
+    assert_eq!(
+        SubRipSubtitle::from_str_strict(valid)
+            .unwrap()
+            .events()
+            .len(),
+        2
+    );
+    let trailing_whitespace = "1 \n00:00:01,000 --> 00:00:02,000\nHello\n";
+    assert!(SubRipSubtitle::from_str_strict(trailing_whitespace).is_ok());
+
+    let garbage = "This is not a subtitle file\n";
+    assert!(SubRipSubtitle::from_str(garbage)
+        .unwrap()
+        .events()
+        .is_empty());
+    assert!(matches!(
+        SubRipSubtitle::from_str_strict(garbage),
+        Err(Error::ParseError { line: 1, .. })
+    ));
+
+    let bad_timing =
+        "1\n00:00:01,000 --> 00:00:02,000\nHello\n\n2\n00:00:03.000 -> 00:00:04,000\nWorld\n";
+    assert!(matches!(
+        SubRipSubtitle::from_str_strict(bad_timing),
+        Err(Error::ParseError { line: 6, .. })
+    ));
+
+    let truncated = "1\n00:00:01,000 --> 00:00:02,000\nHello\n\n2\n";
+    assert!(matches!(
+        SubRipSubtitle::from_str_strict(truncated),
+        Err(Error::ParseError { line: 5, .. })
+    ));
+}
@@ -1,4 +1,9 @@
-use aspasia::{Moment, TimeDelta};
+use std::{collections::HashMap, str::FromStr};
+
+use aspasia::{
+    timing::Frame, Moment, PlainSubtitle, RetainEvents, SubRipSubtitle, Subtitle, TimeDelta,
+    TimedSubtitle,
+};
 
 #[test]
 fn moment_equality() {
@@ -62,6 +67,17 @@ fn delta_operations() {
     assert_eq!(delta, TimeDelta::from(5 * 1000));
 }
 
+#[test]
+fn negation() {
+    assert_eq!(-TimeDelta::from(1000), TimeDelta::from(-1000));
+    assert_eq!(-TimeDelta::from(-1000), TimeDelta::from(1000));
+    assert_eq!(-Moment::from(1000), Moment::from(-1000));
+    assert_eq!(
+        Moment::from(5000) + -TimeDelta::from(1000),
+        Moment::from(4000)
+    );
+}
+
 #[test]
 fn moment_delta_operations() {
     let mut time = Moment::from(0);
@@ -79,3 +95,454 @@ fn moment_delta_operations() {
 
     assert_eq!(time, Moment::from(0));
 }
+
+#[test]
+fn timestamp_decimals() {
+    let moment = Moment::from(3_723_456);
+
+    assert_eq!(moment.as_timestamp('.', 1), "01:02:03.5");
+    assert_eq!(moment.as_timestamp('.', 2), "01:02:03.46");
+    assert_eq!(moment.as_timestamp(',', 3), "01:02:03,456");
+    assert_eq!(moment.as_timestamp('.', 0), "01:02:03");
+}
+
+#[test]
+fn timestamp_rounding_carry() {
+    let moment = Moment::from(59_960);
+
+    assert_eq!(moment.as_timestamp('.', 1), "00:01:00.0");
+    assert_eq!(moment.as_timestamp('.', 2), "00:00:59.96");
+}
+
+#[test]
+fn defaults_and_hashing() {
+    let mut offsets = HashMap::new();
+    offsets.insert(TimeDelta::from(1000), "one second");
+
+    assert_eq!(Moment::default(), Moment::from(0));
+    assert_eq!(TimeDelta::default(), TimeDelta::from(0));
+    assert_eq!(Frame::default(), Frame::from(0));
+    assert_eq!(offsets.get(&TimeDelta::from(1000)), Some(&"one second"));
+}
+
+#[test]
+fn nearest_event() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+First
+
+2
+00:00:04,000 --> 00:00:05,000
+Second
+
+3
+00:00:07,000 --> 00:00:08,000
+Third
+",
+    )
+    .unwrap();
+
+    assert_eq!(srt.nearest_event(Moment::from(1500)).unwrap().0, 0);
+    assert_eq!(srt.nearest_event(Moment::from(3200)).unwrap().0, 1);
+    assert_eq!(srt.nearest_event(Moment::from(6000)).unwrap().0, 1);
+    assert_eq!(
+        srt.nearest_event(Moment::from(60000)).unwrap().1.text,
+        "Third"
+    );
+    assert!(SubRipSubtitle::from_str("")
+        .unwrap()
+        .nearest_event(Moment::from(0))
+        .is_none());
+}
+
+#[test]
+fn parse_any_timestamp() {
+    assert_eq!(
+        Moment::parse_any("01:02:03,456").unwrap(),
+        Moment::from(3_723_456)
+    );
+    assert_eq!(
+        Moment::parse_any("01:02:03.456").unwrap(),
+        Moment::from(3_723_456)
+    );
+    assert_eq!(
+        Moment::parse_any("02:03.456").unwrap(),
+        Moment::from(123_456)
+    );
+    assert_eq!(
+        Moment::parse_any("1:02:03.45").unwrap(),
+        Moment::from(3_723_450)
+    );
+    assert!(Moment::parse_any("1:02:03.45 extra").is_err());
+    assert!(Moment::parse_any("not a timestamp").is_err());
+}
+
+#[test]
+fn split_at_moment() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+Before
+
+2
+00:00:04,000 --> 00:00:06,000
+Straddling
+
+3
+00:00:07,000 --> 00:00:08,000
+After
+",
+    )
+    .unwrap();
+
+    let tail = srt.split_at(Moment::from(5000));
+
+    assert_eq!(srt.events().len(), 2);
+    assert_eq!(srt.event(1).unwrap().end, Moment::from(5000));
+    assert_eq!(srt.event(1).unwrap().line_number, 2);
+    assert_eq!(tail.events().len(), 2);
+    assert_eq!(tail.event(0).unwrap().start, Moment::from(5000));
+    assert_eq!(tail.event(0).unwrap().end, Moment::from(6000));
+    assert_eq!(tail.event(0).unwrap().text, "Straddling");
+    assert_eq!(tail.event(0).unwrap().line_number, 1);
+    assert_eq!(tail.event(1).unwrap().line_number, 2);
+}
+
+#[test]
+fn moment_from_str() {
+    assert_eq!(
+        Moment::from_str("00:01:23,456").unwrap(),
+        Moment::from_timestamp(0, 1, 23, 456)
+    );
+    assert_eq!(
+        Moment::from_str("00:01:23.456").unwrap(),
+        Moment::from(83_456)
+    );
+    assert_eq!("01:23.456".parse::<Moment>().unwrap(), Moment::from(83_456));
+    assert_eq!(
+        "1:01:23.456".parse::<Moment>().unwrap(),
+        Moment::from(3_683_456)
+    );
+    assert_eq!(
+        Moment::from_str("00:00:61,000").unwrap(),
+        Moment::from_timestamp(0, 1, 1, 0)
+    );
+    assert!(Moment::from_str("00:01").is_err());
+}
+
+#[test]
+fn display_timings() {
+    assert_eq!(Moment::from(3_723_456).to_string(), "01:02:03.456");
+    assert_eq!(Moment::from(-1500).to_string(), "-00:00:01.500");
+    assert_eq!(TimeDelta::from(61_000).to_string(), "+00:01:01.000");
+    assert_eq!(TimeDelta::from(-250).to_string(), "-00:00:00.250");
+    assert_eq!(TimeDelta::from(0).to_string(), "+00:00:00.000");
+}
+
+#[test]
+fn fix_swapped_timings() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:02,000 --> 00:00:01,000
+Swapped
+
+2
+00:00:03,000 --> 00:00:04,000
+Fine
+",
+    )
+    .unwrap();
+
+    assert_eq!(srt.fix_swapped_timings(), 1);
+    assert_eq!(srt.event(0).unwrap().start, Moment::from(1000));
+    assert_eq!(srt.event(0).unwrap().end, Moment::from(2000));
+    assert_eq!(srt.event(1).unwrap().start, Moment::from(3000));
+    assert_eq!(srt.fix_swapped_timings(), 0);
+}
+
+#[test]
+fn rescale_timings() {
+    let text = "1
+00:00:01,000 --> 00:00:02,000
+First
+
+2
+00:00:10,000 --> 00:00:12,500
+Second
+";
+    let mut srt = SubRipSubtitle::from_str(text).unwrap();
+
+    srt.rescale(1.0);
+    assert_eq!(
+        srt.to_string(),
+        SubRipSubtitle::from_str(text).unwrap().to_string()
+    );
+
+    srt.rescale(0.5);
+    assert_eq!(srt.event(0).unwrap().start, Moment::from(500));
+    assert_eq!(srt.event(1).unwrap().end, Moment::from(6250));
+
+    let mut srt = SubRipSubtitle::from_str(text).unwrap();
+    srt.rescale_around(2.0, Moment::from(10_000));
+    assert_eq!(srt.event(0).unwrap().start, Moment::from(0));
+    assert_eq!(srt.event(0).unwrap().end, Moment::from(0));
+    assert_eq!(srt.event(1).unwrap().start, Moment::from(10_000));
+    assert_eq!(srt.event(1).unwrap().end, Moment::from(15_000));
+}
+
+#[test]
+fn time_delta_frames() {
+    assert_eq!(TimeDelta::from(1000).as_frames(24.0), 24);
+    assert_eq!(TimeDelta::from(1000).as_frames(25.0), 25);
+    assert_eq!(TimeDelta::from(500).as_frames(25.0), 13);
+    assert_eq!(TimeDelta::from(-2000).as_frames(24.0), -48);
+    assert_eq!(TimeDelta::from(500).format_frames(24.0), "+12 frames");
+    assert_eq!(TimeDelta::from(-40).format_frames(25.0), "-1 frame");
+}
+
+#[test]
+fn sort_events_by_time() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:05,000 --> 00:00:06,000
+Third
+
+2
+00:00:01,000 --> 00:00:03,000
+Second
+
+3
+00:00:01,000 --> 00:00:02,000
+First
+",
+    )
+    .unwrap();
+
+    srt.sort_by_time();
+
+    let texts: Vec<&str> = srt.events().iter().map(|e| e.text.as_str()).collect();
+    assert_eq!(texts, vec!["First", "Second", "Third"]);
+    assert_eq!(srt.event(0).unwrap().line_number, 3);
+}
+
+#[test]
+fn fix_overlapping_events() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:03,000
+Overlapping
+
+2
+00:00:02,000 --> 00:00:04,000
+Next
+
+3
+00:00:04,000 --> 00:00:10,000
+Container
+
+4
+00:00:05,000 --> 00:00:06,000
+Contained
+",
+    )
+    .unwrap();
+
+    srt.fix_overlaps();
+
+    assert_eq!(srt.event(0).unwrap().end, Moment::from(1999));
+    assert_eq!(srt.event(1).unwrap().end, Moment::from(3999));
+    assert_eq!(srt.event(2).unwrap().end, Moment::from(4999));
+    assert_eq!(srt.event(3).unwrap().start, Moment::from(5000));
+
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:05,000
+Container
+
+2
+00:00:01,000 --> 00:00:02,000
+Simultaneous
+",
+    )
+    .unwrap();
+
+    srt.fix_overlaps_with_gap(TimeDelta::from(100));
+
+    assert_eq!(srt.event(0).unwrap().text, "Simultaneous");
+    assert_eq!(srt.event(1).unwrap().start, Moment::from(2100));
+    assert_eq!(srt.event(1).unwrap().end, Moment::from(6100));
+}
+
+#[test]
+fn shift_tail_and_range() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+One
+
+2
+00:00:03,000 --> 00:00:04,000
+Two
+
+3
+00:00:05,000 --> 00:00:06,000
+Three
+",
+    )
+    .unwrap();
+
+    srt.shift_from(1, TimeDelta::from(500));
+    assert_eq!(srt.event(0).unwrap().start, Moment::from(1000));
+    assert_eq!(srt.event(1).unwrap().start, Moment::from(3500));
+    assert_eq!(srt.event(2).unwrap().end, Moment::from(6500));
+
+    srt.shift_range(0..1, TimeDelta::from(-1000));
+    assert_eq!(srt.event(0).unwrap().start, Moment::from(0));
+    assert_eq!(srt.event(1).unwrap().start, Moment::from(3500));
+
+    srt.shift_range(2..10, TimeDelta::from(100));
+    srt.shift_from(10, TimeDelta::from(100));
+    assert_eq!(srt.event(2).unwrap().start, Moment::from(5600));
+}
+
+#[test]
+fn seconds_f64_conversions() {
+    assert_eq!(Moment::from_seconds_f64(1.5005), Moment::from(1501));
+    assert_eq!(Moment::from_seconds_f64(1.2344), Moment::from(1234));
+    assert_eq!(Moment::from_seconds_f64(-0.25), Moment::from(-250));
+    assert_eq!(TimeDelta::from_seconds_f64(-1.5005), TimeDelta::from(-1501));
+
+    assert!((Moment::from(1234).as_seconds_f64() - 1.234).abs() < f64::EPSILON);
+    assert!((TimeDelta::from(-500).as_seconds_f64() + 0.5).abs() < f64::EPSILON);
+
+    for ms in [0, 1, 999, 1001, 3_723_456] {
+        let moment = Moment::from(ms);
+        assert_eq!(Moment::from_seconds_f64(moment.as_seconds_f64()), moment);
+    }
+}
+
+#[test]
+fn estimate_and_sync_offset() {
+    let reference = SubRipSubtitle::from_str(
+        "1
+00:00:01,000 --> 00:00:02,000
+One
+
+2
+00:00:04,500 --> 00:00:06,000
+Two
+
+3
+00:00:09,200 --> 00:00:10,000
+Three
+
+4
+00:00:15,000 --> 00:00:16,000
+Four
+",
+    )
+    .unwrap();
+
+    let mut offset = reference.clone();
+    offset.shift(TimeDelta::from(-2350));
+    // A missing event shouldn't affect the result
+    offset.retain_events(|event| event.line_number != 3);
+
+    assert_eq!(offset.estimate_offset(&reference), TimeDelta::from(2350));
+    assert_eq!(offset.sync_to(&reference), TimeDelta::from(2350));
+    assert_eq!(offset.event(0).unwrap().start, Moment::from(1000));
+    assert_eq!(offset.event(2).unwrap().end, Moment::from(16000));
+
+    let empty = SubRipSubtitle::from_str("").unwrap();
+    assert_eq!(empty.estimate_offset(&reference), TimeDelta::from(0));
+}
+
+#[test]
+fn plain_from_lines() {
+    let plain = PlainSubtitle::from_lines(
+        &["First line", "Second line", "Third line"],
+        Moment::from(1000),
+        TimeDelta::from(2000),
+    );
+    let events = plain.events();
+
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0].text, "First line");
+    assert_eq!(events[0].start, Moment::from(1000));
+    assert_eq!(events[0].end, Moment::from(3000));
+    assert_eq!(events[1].start, Moment::from(3000));
+    assert_eq!(events[1].end, Moment::from(5000));
+    assert_eq!(events[2].text, "Third line");
+    assert_eq!(events[2].start, Moment::from(5000));
+    assert_eq!(events[2].end, Moment::from(7000));
+}
+
+#[test]
+fn moment_lerp() {
+    let a = Moment::from(0);
+    let b = Moment::from(1000);
+
+    assert_eq!(Moment::lerp(a, b, 0.25), Moment::from(250));
+    assert_eq!(Moment::lerp(a, b, 0.0), a);
+    assert_eq!(Moment::lerp(a, b, 1.0), b);
+    assert_eq!(Moment::lerp(a, b, 1.5), b);
+    assert_eq!(Moment::lerp(a, b, -0.5), a);
+    assert_eq!(Moment::lerp(b, a, 0.25), Moment::from(750));
+}
+
+#[test]
+fn total_duration() {
+    let srt = SubRipSubtitle::from_str(
+        "1
+00:00:05,000 --> 00:00:06,000
+Middle
+
+2
+00:00:01,000 --> 00:00:10,000
+Longest
+
+3
+00:00:02,000 --> 00:00:03,000
+Early
+",
+    )
+    .unwrap();
+
+    assert_eq!(srt.last_end(), Some(Moment::from(10000)));
+    assert_eq!(srt.total_duration(), Some(TimeDelta::from(9000)));
+
+    let empty = SubRipSubtitle::from_str("").unwrap();
+    assert_eq!(empty.last_end(), None);
+    assert_eq!(empty.total_duration(), None);
+}
+
+#[test]
+fn padding() {
+    let mut srt = SubRipSubtitle::from_str(
+        "1
+00:00:00,100 --> 00:00:01,000
+Near zero
+
+2
+00:00:01,100 --> 00:00:02,000
+Close to previous
+
+3
+00:00:05,000 --> 00:00:06,000
+Far from others
+",
+    )
+    .unwrap();
+    srt.pad(TimeDelta::from(250), TimeDelta::from(250));
+
+    let timings: Vec<_> = srt
+        .events()
+        .iter()
+        .map(|event| (i64::from(event.start), i64::from(event.end)))
+        .collect();
+    assert_eq!(timings, vec![(0, 1100), (1100, 2250), (4750, 6250)]);
+    for pair in srt.events().windows(2) {
+        assert!(pair[0].end <= pair[1].start);
+    }
+}
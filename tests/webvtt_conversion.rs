@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use aspasia::{AssSubtitle, SubRipSubtitle, Subtitle, WebVttSubtitle};
+use aspasia::{AssSubtitle, Moment, SubRipSubtitle, Subtitle, WebVttSubtitle};
 
 #[test]
 fn to_srt_formatting() {
@@ -40,3 +40,66 @@ And yet..
         "This {\\i1}is{\\i0} {\\b1}completely{\\b0}{\\u1} wrong{\\u0}.\\NAnd yet.."
     );
 }
+
+#[test]
+fn to_ass_alignment() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:00.000 --> 00:00:01.000 line:0% align:center
+Top
+
+00:00:01.000 --> 00:00:02.000 line:0 align:start
+Top left
+
+00:00:02.000 --> 00:00:03.000 line:50%
+Middle
+
+00:00:03.000 --> 00:00:04.000 line:-1 align:end
+Bottom right
+
+00:00:04.000 --> 00:00:05.000
+Default
+",
+    )
+    .unwrap();
+    let ass = AssSubtitle::from(&vtt);
+
+    assert_eq!(ass.event(0).unwrap().text, "{\\an8}Top");
+    assert_eq!(ass.event(1).unwrap().text, "{\\an7}Top left");
+    assert_eq!(ass.event(2).unwrap().text, "{\\an5}Middle");
+    assert_eq!(ass.event(3).unwrap().text, "{\\an3}Bottom right");
+    assert_eq!(ass.event(4).unwrap().text, "Default");
+}
+
+#[test]
+fn to_ass_by_voice() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:01.000 --> 00:00:03.000
+<v Alice>Where are you going?</v>
+<v Bob><i>Outside.</i></v>
+
+00:00:04.000 --> 00:00:05.000
+No speaker
+",
+    )
+    .unwrap();
+    let ass = AssSubtitle::from_webvtt_by_voice(&vtt);
+
+    assert_eq!(ass.events().len(), 3);
+    let alice = ass.event(0).unwrap();
+    assert_eq!(alice.name.as_deref(), Some("Alice"));
+    assert_eq!(alice.text, "Where are you going?");
+    assert_eq!(alice.start, Moment::from(1000));
+    assert_eq!(alice.end, Moment::from(3000));
+    let bob = ass.event(1).unwrap();
+    assert_eq!(bob.name.as_deref(), Some("Bob"));
+    assert_eq!(bob.text, "{\\i1}Outside.{\\i0}");
+    assert_eq!(bob.start, Moment::from(1000));
+    assert_eq!(ass.event(2).unwrap().name, None);
+    assert_eq!(ass.event(2).unwrap().text, "No speaker");
+
+    assert_eq!(AssSubtitle::from(&vtt).events().len(), 2);
+}
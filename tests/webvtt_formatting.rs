@@ -1,6 +1,9 @@
 use std::str::FromStr;
 
-use aspasia::{Subtitle, TextSubtitle, WebVttSubtitle};
+use aspasia::{
+    webvtt::{WebVttBlock, WebVttCue},
+    Moment, RetainEvents, Subtitle, TextSubtitle, TimedSubtitle, WebVttSubtitle,
+};
 
 #[test]
 fn strip_vtt_format() {
@@ -47,6 +50,8 @@ everything
 00:00:00.650 --> 00:00:01.200
 something
 
+NOTE ignore this please
+
 00:00:04.000 --> 00:00:06.000
 anything
 
@@ -93,3 +98,335 @@ baz
 "
     );
 }
+
+#[test]
+fn cue_settings_output() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:00.000 --> 00:00:02.520 align:left line:0
+Text
+",
+    )
+    .unwrap();
+
+    assert!(vtt
+        .to_string()
+        .contains("\n00:00:00.000 --> 00:00:02.520 align:left line:0\nText"));
+}
+
+#[test]
+fn add_style_and_region() {
+    let mut vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:00.000 --> 00:00:01.000 region:fred
+Text
+",
+    )
+    .unwrap();
+
+    vtt.add_style("::cue {\n  color: yellow;\n}");
+    vtt.add_style("::cue(b) {\n  color: peachpuff;\n}");
+    vtt.add_region("id:fred\nwidth:40%");
+    vtt.add_region("id:bill\nwidth:40%");
+
+    assert_eq!(vtt.remove_region(1).as_deref(), Some("id:bill\nwidth:40%"));
+    assert_eq!(vtt.remove_region(1), None);
+
+    let reparsed = WebVttSubtitle::from_str(vtt.to_string().as_str()).unwrap();
+
+    assert_eq!(
+        reparsed.styles(),
+        [
+            "::cue {\n  color: yellow;\n}",
+            "::cue(b) {\n  color: peachpuff;\n}"
+        ]
+    );
+    assert_eq!(reparsed.regions(), ["id:fred\nwidth:40%"]);
+    assert_eq!(reparsed.events().len(), 1);
+    assert!(reparsed.undefined_region_references().is_empty());
+
+    vtt.remove_style(0);
+
+    assert_eq!(vtt.styles().len(), 1);
+    assert!(vtt.to_string().starts_with("WEBVTT\n\nSTYLE\n::cue(b)"));
+}
+
+#[test]
+fn empty_header() {
+    let mut vtt = WebVttSubtitle::from_str("WEBVTT\n").unwrap();
+
+    vtt.set_header(String::new());
+    assert_eq!(vtt.to_string(), "WEBVTT\n");
+
+    vtt.set_header("Title".to_string());
+    assert_eq!(vtt.to_string(), "WEBVTT - Title\n");
+}
+
+#[test]
+fn cue_settings_whitespace_roundtrip() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:00.000 --> 00:00:02.520   align:start \t line:0  
+Text
+",
+    )
+    .unwrap();
+    let output = vtt.to_string();
+
+    assert_eq!(
+        vtt.event(0).unwrap().settings.as_deref(),
+        Some("align:start line:0")
+    );
+    assert!(output.contains("\n00:00:00.000 --> 00:00:02.520 align:start line:0\nText"));
+    assert_eq!(
+        WebVttSubtitle::from_str(&output).unwrap().to_string(),
+        output
+    );
+}
+
+#[test]
+fn notes_roundtrip() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+NOTE Created by someone
+
+NOTE
+Spanning
+multiple lines
+
+00:00:00.000 --> 00:00:01.000
+Text
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        vtt.notes(),
+        &["Created by someone", "Spanning\nmultiple lines"]
+    );
+    assert_eq!(
+        vtt.to_string(),
+        "WEBVTT
+
+NOTE Created by someone
+
+NOTE
+Spanning
+multiple lines
+
+00:00:00.000 --> 00:00:01.000
+Text
+"
+    );
+}
+
+#[test]
+fn block_ordering() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+NOTE Before styles
+
+STYLE
+::cue {
+  color: yellow;
+}
+
+00:00:00.000 --> 00:00:01.000
+First
+
+REGION
+id:fred
+width:40%
+
+NOTE Between cues
+
+00:00:01.000 --> 00:00:02.000
+Second
+
+NOTE At the end
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        vtt.to_string(),
+        "WEBVTT
+
+NOTE Before styles
+
+STYLE
+::cue {
+  color: yellow;
+}
+
+REGION
+id:fred
+width:40%
+
+00:00:00.000 --> 00:00:01.000
+First
+
+NOTE Between cues
+
+00:00:01.000 --> 00:00:02.000
+Second
+
+NOTE At the end
+"
+    );
+}
+
+#[test]
+fn block_ordering_after_editing() {
+    let mut vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+STYLE
+::cue {
+  color: yellow;
+}
+
+NOTE After styles
+
+00:00:05.000 --> 00:00:06.000
+First
+
+NOTE Before second
+
+00:00:01.000 --> 00:00:02.000
+Second
+
+00:00:07.000 --> 00:00:08.000
+Third
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        vtt.blocks(),
+        [
+            WebVttBlock::Style(0),
+            WebVttBlock::Note(0),
+            WebVttBlock::Cue(0),
+            WebVttBlock::Note(1),
+            WebVttBlock::Cue(1),
+            WebVttBlock::Cue(2),
+        ]
+    );
+    assert!(vtt
+        .to_string()
+        .contains("}\n\nNOTE After styles\n\n00:00:05.000"));
+
+    vtt.sort_by_time();
+    assert!(vtt
+        .to_string()
+        .contains("NOTE After styles\n\nNOTE Before second\n\n00:00:01.000 --> 00:00:02.000\nSecond\n\n00:00:05.000"));
+
+    vtt.retain_events(|cue| cue.text != "Second");
+    assert!(vtt
+        .to_string()
+        .contains("First\n\nNOTE Before second\n\n00:00:07.000 --> 00:00:08.000\nThird"));
+}
+
+#[test]
+fn build_cues() {
+    let mut vtt = WebVttSubtitle::default();
+
+    vtt.push_cue(
+        WebVttCue::builder()
+            .start(Moment::from(0))
+            .end(Moment::from(1500))
+            .text("First")
+            .build(),
+    );
+    vtt.push_cue(
+        WebVttCue::builder()
+            .identifier("second")
+            .settings("align:start")
+            .start(Moment::from(2000))
+            .end(Moment::from(3000))
+            .text("Second")
+            .build(),
+    );
+
+    assert_eq!(vtt.event(0).unwrap().identifier, None);
+    assert_eq!(vtt.event(0).unwrap().settings, None);
+    assert_eq!(
+        vtt.to_string(),
+        "WEBVTT
+
+00:00:00.000 --> 00:00:01.500
+First
+
+second
+00:00:02.000 --> 00:00:03.000 align:start
+Second
+"
+    );
+}
+
+#[test]
+fn right_to_left_marks() {
+    let mut vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:00.000 --> 00:00:01.000
+\u{202B}مرحبا\u{202C}
+",
+    )
+    .unwrap();
+
+    vtt.strip_bidi_marks();
+    assert_eq!(vtt.event(0).unwrap().text, "مرحبا");
+
+    vtt.mark_rtl();
+    vtt.mark_rtl();
+    assert_eq!(vtt.event(0).unwrap().text, "\u{200F}مرحبا");
+}
+
+#[test]
+fn empty_cue_roundtrip() {
+    let mut vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:01.000 --> 00:02.000
+First
+
+00:03.000 --> 00:04.000
+Second
+",
+    )
+    .unwrap();
+    vtt.event_mut(0).unwrap().text = String::new();
+
+    let output = vtt.to_string();
+    assert!(output.contains("00:00:01.000 --> 00:00:02.000\n\n\n00:00:03.000"));
+
+    let reparsed = WebVttSubtitle::from_str(output.as_str()).unwrap();
+    assert_eq!(reparsed.events().len(), 2);
+    assert_eq!(reparsed.event(0).unwrap().text, "");
+    assert_eq!(reparsed.event(1).unwrap().text, "Second");
+}
+
+#[test]
+fn builder() {
+    let vtt = WebVttSubtitle::builder()
+        .header("Built")
+        .styles(vec!["::cue {\n  color: yellow;\n}".to_string()])
+        .cues(vec![WebVttCue::builder()
+            .start(Moment::from(0))
+            .end(Moment::from(1500))
+            .text("Text")
+            .build()])
+        .build();
+
+    assert_eq!(
+        vtt.to_string(),
+        "WEBVTT - Built\n\nSTYLE\n::cue {\n  color: yellow;\n}\n\n00:00:00.000 --> 00:00:01.500\nText\n"
+    );
+}
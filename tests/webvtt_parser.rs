@@ -1,6 +1,8 @@
 use std::str::FromStr;
 
-use aspasia::{Subtitle, WebVttSubtitle};
+use aspasia::{
+    webvtt::WebVttCueSettings, Error, Moment, Subtitle, TextEvent, TimedSubtitle, WebVttSubtitle,
+};
 
 #[test]
 fn trailing_newlines() {
@@ -85,3 +87,275 @@ More text
     assert_eq!(vtt.event(0).unwrap().text, "Text");
     assert_eq!(vtt.event(1).unwrap().text, "More text");
 }
+
+#[test]
+fn undefined_region_references() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+REGION
+id:fred
+width:40%
+lines:3
+
+00:00:00.000 --> 00:00:05.000 region:fred align:left
+Hi, my name is Fred
+
+00:00:05.000 --> 00:00:10.000 region:bill align:right
+Hi, I'm Bill
+
+00:00:10.000 --> 00:00:15.000
+Nobody here
+",
+    )
+    .unwrap();
+
+    assert_eq!(vtt.undefined_region_references(), vec![1]);
+}
+
+#[test]
+fn truncated_file() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:01.000 --> 00:00:02.000
+First
+
+00:00:03.000 --> 00:00:04.000
+Second line is cu",
+    )
+    .unwrap();
+
+    assert_eq!(vtt.events().len(), 2);
+    assert_eq!(vtt.event(0).unwrap().text, "First");
+    assert_eq!(vtt.event(1).unwrap().text, "Second line is cu");
+
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:01.000 --> 00:00:02.000
+First
+
+00:00:03.000 --> 00:0",
+    )
+    .unwrap();
+
+    assert_eq!(vtt.events().len(), 1);
+    assert_eq!(vtt.event(0).unwrap().text, "First");
+}
+
+#[test]
+fn comma_timestamps() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:01,500 --> 00:00:02,520
+Hello
+
+00:03,000 --> 00:04.000
+World
+",
+    )
+    .unwrap();
+
+    assert_eq!(vtt.events().len(), 2);
+    assert_eq!(vtt.event(0).unwrap().start, 1500.into());
+    assert_eq!(vtt.event(0).unwrap().end, 2520.into());
+    assert_eq!(vtt.event(1).unwrap().start, 3000.into());
+    assert!(vtt
+        .to_string()
+        .contains("00:00:01.500 --> 00:00:02.520\nHello"));
+}
+
+#[test]
+fn typed_cue_settings() {
+    let mut vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:00.000 --> 00:00:01.000 align:start position:10%,line-left unknown:1 line:90%,end
+Text
+",
+    )
+    .unwrap();
+    let cue = vtt.event_mut(0).unwrap();
+    let mut settings = cue.parsed_settings();
+
+    assert_eq!(
+        settings,
+        WebVttCueSettings {
+            line: Some("90%,end".to_string()),
+            position: Some("10%,line-left".to_string()),
+            align: Some("start".to_string()),
+            ..WebVttCueSettings::default()
+        }
+    );
+
+    settings.size = Some("50%".to_string());
+    settings.align = None;
+    cue.set_settings(&settings);
+
+    assert_eq!(
+        cue.settings.as_deref(),
+        Some("line:90%,end position:10%,line-left size:50%")
+    );
+
+    cue.set_settings(&WebVttCueSettings::default());
+    assert_eq!(cue.settings, None);
+}
+
+#[test]
+fn voice_spans() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:00.000 --> 00:00:01.000
+<v Roger Bingham>We are in <i>New York City</i>
+
+00:00:01.000 --> 00:00:02.000
+Narration <v.loud Esme>Hello</v> <v Mary>Hi
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        vtt.event(0).unwrap().voices(),
+        vec![(
+            Some("Roger Bingham".to_string()),
+            "We are in New York City".to_string()
+        )]
+    );
+    assert_eq!(
+        vtt.event(1).unwrap().voices(),
+        vec![
+            (None, "Narration".to_string()),
+            (Some("Esme".to_string()), "Hello".to_string()),
+            (Some("Mary".to_string()), "Hi".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn strict_timestamps() {
+    let valid = "WEBVTT
+
+00:01.000 --> 00:02.500
+Short
+
+01:00:01.000 --> 01:00:02.000 align:left
+Long
+";
+    let vtt = WebVttSubtitle::from_str_strict(valid).unwrap();
+    assert_eq!(vtt.events().len(), 2);
+
+    let malformed = "WEBVTT
+
+00:01.000 --> 00:02.500
+Fine
+
+intro
+00:03.000 --> 1:2:3.4
+Broken
+";
+    assert!(WebVttSubtitle::from_str(malformed).is_ok());
+    assert!(matches!(
+        WebVttSubtitle::from_str_strict(malformed),
+        Err(Error::ParseError { line: 7, message }) if message.contains("\"1:2:3.4\"")
+    ));
+    assert!(matches!(
+        WebVttSubtitle::from_str_strict("WEBVTT\n\n00:01,000 --> 00:02.000\nComma\n"),
+        Err(Error::ParseError { line: 3, .. })
+    ));
+}
+
+#[test]
+fn merge_styles() {
+    let mut cues = WebVttSubtitle::from_str(
+        "WEBVTT
+
+REGION
+id:fred
+width:20%
+
+00:00:00.000 --> 00:00:05.000 region:fred
+<c.yellow>Hi, my name is Fred</c>
+",
+    )
+    .unwrap();
+    let styles = WebVttSubtitle::from_str(
+        "WEBVTT
+
+STYLE
+::cue(.yellow) {
+  color: yellow;
+}
+
+REGION
+id:fred
+width:40%
+",
+    )
+    .unwrap();
+
+    cues.merge_styles_from(&styles);
+    cues.merge_styles_from(&styles);
+
+    assert_eq!(cues.styles(), styles.styles());
+    assert_eq!(cues.regions(), styles.regions());
+    assert!(cues.undefined_region_references().is_empty());
+    assert_eq!(cues.events().len(), 1);
+}
+
+#[test]
+fn dialogue_lines() {
+    let vtt = WebVttSubtitle::from_str(
+        "WEBVTT
+
+00:00:01.000 --> 00:00:03.000
+<v Fred>Hi, <i>Bill</i></v>
+<v Bill>Hello
+
+00:00:04.000 --> 00:00:05.000
+Nobody
+
+00:00:05.000 --> 00:00:06.000
+<v Fred>Bye</v>
+
+00:00:06.000 --> 00:00:07.000
+ 
+",
+    )
+    .unwrap();
+
+    assert_eq!(vtt.event(0).unwrap().speaker(), None);
+    assert_eq!(vtt.event(1).unwrap().speaker(), None);
+    assert_eq!(vtt.event(2).unwrap().speaker().as_deref(), Some("Fred"));
+    assert_eq!(
+        vtt.to_dialogue_lines(),
+        vec![
+            (
+                Some("Fred".to_string()),
+                Moment::from(1000),
+                Moment::from(3000),
+                "Hi, Bill".to_string()
+            ),
+            (
+                Some("Bill".to_string()),
+                Moment::from(1000),
+                Moment::from(3000),
+                "Hello".to_string()
+            ),
+            (
+                None,
+                Moment::from(4000),
+                Moment::from(5000),
+                "Nobody".to_string()
+            ),
+            (
+                Some("Fred".to_string()),
+                Moment::from(5000),
+                Moment::from(6000),
+                "Bye".to_string()
+            ),
+        ]
+    );
+}